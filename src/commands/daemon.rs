@@ -0,0 +1,215 @@
+use std::thread;
+use std::time::Duration;
+use chrono::Utc;
+use crate::commands::process;
+use crate::config::KenConfig;
+use crate::error::Result;
+use crate::executor;
+use crate::session::{Event, SessionStatus};
+use crate::storage::{find_project_root, open_storage, Storage};
+
+/// Run the daemon command - continuously evaluate and dispatch sessions
+pub fn run(
+    max_concurrency: usize,
+    poll_interval: Duration,
+    watch: bool,
+    execute: bool,
+    active_timeout: Option<Duration>,
+) -> Result<()> {
+    let storage = open_storage()?;
+    let config = if execute {
+        Some(KenConfig::load(&find_project_root()?)?)
+    } else {
+        None
+    };
+
+    run_daemon_with_storage(&storage, max_concurrency, poll_interval, watch, config.as_ref(), active_timeout)
+}
+
+/// Daemon command implementation with injected storage (for testing)
+///
+/// Each tick wakes any sleeping sessions whose trigger is now satisfied, fails
+/// out any `Active` session stale for longer than `active_timeout` (if set),
+/// then activates pending sessions until `max_concurrency` sessions are
+/// `Active`. With `config` set, each newly activated session's agent is run
+/// directly via the `executor` module; otherwise a `spawn` line is printed
+/// per session for the caller to act on. Without `--watch` the daemon exits
+/// as soon as `has_work_with_storage` reports nothing left to do; with
+/// `--watch` it stays resident and keeps polling.
+pub fn run_daemon_with_storage(
+    storage: &Storage,
+    max_concurrency: usize,
+    poll_interval: Duration,
+    watch: bool,
+    config: Option<&KenConfig>,
+    active_timeout: Option<Duration>,
+) -> Result<()> {
+    loop {
+        tick(storage, max_concurrency, config, active_timeout)?;
+
+        if !watch && !process::has_work_with_storage(storage)? {
+            break;
+        }
+
+        thread::sleep(poll_interval);
+    }
+
+    Ok(())
+}
+
+/// Run a single daemon tick: wake satisfied sleeping sessions, recover stale
+/// active sessions, retry failed sessions with retries remaining, then
+/// activate pending sessions up to `max_concurrency` total active sessions.
+fn tick(
+    storage: &Storage,
+    max_concurrency: usize,
+    config: Option<&KenConfig>,
+    active_timeout: Option<Duration>,
+) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+
+    process::wake_satisfied_sessions(storage, &now)?;
+    if let Some(active_timeout) = active_timeout {
+        process::recover_stale_active_sessions(storage, &now, active_timeout)?;
+    }
+    process::retry_failed_sessions(storage, &now)?;
+
+    let active_count = storage.get_sessions_by_status(SessionStatus::Active)?.len();
+    let mut free_slots = max_concurrency.saturating_sub(active_count);
+
+    if free_slots == 0 {
+        return Ok(());
+    }
+
+    let pending = storage.get_sessions_by_status(SessionStatus::Pending)?;
+    for session in &pending {
+        if free_slots == 0 {
+            break;
+        }
+
+        let activated = storage.try_update_session_status(
+            &session.id,
+            SessionStatus::Pending,
+            SessionStatus::Active,
+            &now,
+        )?;
+
+        if activated {
+            storage.insert_event(&Event::new("session_activated", Some(&session.id), None))?;
+
+            let children_results = match &session.trigger {
+                Some(trigger_json) => process::collect_children_results(storage, trigger_json)?,
+                None => None,
+            };
+            if let Some(results) = &children_results {
+                storage.insert_event(&Event::new(
+                    "results_aggregated",
+                    Some(&session.id),
+                    Some(serde_json::to_string(results)?),
+                ))?;
+            }
+
+            match config {
+                Some(config) => executor::execute_session(storage, config, session, children_results.as_deref())?,
+                None => {
+                    let mut output = serde_json::json!({
+                        "action": "spawn",
+                        "session": {
+                            "id": session.id,
+                            "ken": session.ken,
+                            "task": session.task,
+                            "checkpoint": session.checkpoint,
+                        }
+                    });
+                    if let Some(results) = &children_results {
+                        output["session"]["children_results"] = serde_json::to_value(results)?;
+                    }
+                    println!("{}", serde_json::to_string(&output)?);
+                }
+            }
+
+            free_slots -= 1;
+        }
+        // If activation failed (race condition), move on to the next pending session.
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use tempfile::tempdir;
+
+    fn create_test_storage() -> (tempfile::TempDir, Storage) {
+        let dir = tempdir().unwrap();
+        let ken_dir = dir.path().join(".ken");
+        std::fs::create_dir(&ken_dir).unwrap();
+        let db_path = ken_dir.join("ken.db");
+        let storage = Storage::create(&db_path).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn test_daemon_activates_up_to_max_concurrency() {
+        let (_dir, storage) = create_test_storage();
+
+        for i in 0..3 {
+            let session = Session::with_id(&format!("s{}", i), "test/ken", "task", None);
+            storage.insert_session(&session).unwrap();
+        }
+
+        tick(&storage, 2, None, None).unwrap();
+
+        let active = storage.get_sessions_by_status(SessionStatus::Active).unwrap();
+        let pending = storage.get_sessions_by_status(SessionStatus::Pending).unwrap();
+        assert_eq!(active.len(), 2);
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_daemon_respects_existing_active_sessions() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut already_active = Session::with_id("active-1", "test/ken", "task", None);
+        already_active.status = SessionStatus::Active;
+        storage.insert_session(&already_active).unwrap();
+
+        let pending = Session::with_id("pending-1", "test/ken", "task", None);
+        storage.insert_session(&pending).unwrap();
+
+        tick(&storage, 1, None, None).unwrap();
+
+        let still_pending = storage.get_session("pending-1").unwrap();
+        assert_eq!(still_pending.status, SessionStatus::Pending);
+    }
+
+    #[test]
+    fn test_daemon_exits_without_watch_when_no_work() {
+        let (_dir, storage) = create_test_storage();
+
+        let result = run_daemon_with_storage(&storage, 1, Duration::from_millis(1), false, None, None);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_daemon_wakes_sleeping_session_before_activating() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "child task", None);
+        child.status = SessionStatus::Complete;
+        storage.insert_session(&child).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "parent task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        tick(&storage, 1, None, None).unwrap();
+
+        let updated = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Active);
+    }
+}
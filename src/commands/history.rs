@@ -0,0 +1,57 @@
+use crate::error::Result;
+use crate::storage::{open_storage, Storage};
+
+/// Run the history command - reconstruct `id`'s state as of `at` from its event log
+pub fn run(id: &str, at: &str) -> Result<()> {
+    let storage = open_storage()?;
+    run_with_storage(&storage, id, at)
+}
+
+/// History command implementation with injected storage (for testing)
+pub fn run_with_storage(storage: &Storage, id: &str, at: &str) -> Result<()> {
+    let session = storage.reconstruct_session_at(id, at)?;
+    println!("{}", serde_json::to_string_pretty(&session)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::{Session, SessionStatus};
+    use tempfile::tempdir;
+
+    fn create_test_storage() -> (tempfile::TempDir, Storage) {
+        let dir = tempdir().unwrap();
+        let ken_dir = dir.path().join(".ken");
+        std::fs::create_dir(&ken_dir).unwrap();
+        let db_path = ken_dir.join("ken.db");
+        let storage = Storage::create(&db_path).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn test_history_reconstructs_past_state() {
+        let (_dir, storage) = create_test_storage();
+
+        let session = Session::with_id("s1", "test/ken", "task", None);
+        storage.insert_session(&session).unwrap();
+
+        let before = chrono::Utc::now().to_rfc3339();
+        storage.complete_session("s1", "done", &chrono::Utc::now().to_rfc3339()).unwrap();
+
+        let result = run_with_storage(&storage, "s1", &before);
+
+        assert!(result.is_ok());
+        let reconstructed = storage.reconstruct_session_at("s1", &before).unwrap();
+        assert_eq!(reconstructed.status, SessionStatus::Pending);
+    }
+
+    #[test]
+    fn test_history_unknown_session_errors() {
+        let (_dir, storage) = create_test_storage();
+
+        let result = run_with_storage(&storage, "missing", &chrono::Utc::now().to_rfc3339());
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,9 @@
+pub mod init;
+pub mod wake;
+pub mod request;
+pub mod process;
+pub mod status;
+pub mod daemon;
+pub mod serve;
+pub mod tree;
+pub mod history;
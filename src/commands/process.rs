@@ -1,19 +1,234 @@
-use chrono::Utc;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use crate::config::KenConfig;
 use crate::error::Result;
-use crate::session::{SessionStatus, Trigger, Event};
-use crate::storage::{open_storage, Storage};
+use crate::executor;
+use crate::session::{ChildResult, OneOrVec, Session, SessionStatus, Trigger, Event};
+use crate::storage::{find_project_root, open_storage, Storage};
 
 /// Run the process command - evaluate triggers and activate one pending session
-pub fn run() -> Result<()> {
+pub fn run(execute: bool, active_timeout: Option<Duration>) -> Result<()> {
     let storage = open_storage()?;
-    run_with_storage(&storage)
+
+    if execute {
+        let config = KenConfig::load(&find_project_root()?)?;
+        run_with_storage_executing(&storage, &config, active_timeout)
+    } else {
+        run_with_storage(&storage, active_timeout)
+    }
 }
 
 /// Process command implementation with injected storage (for testing)
-pub fn run_with_storage(storage: &Storage) -> Result<()> {
+pub fn run_with_storage(storage: &Storage, active_timeout: Option<Duration>) -> Result<()> {
     let now = Utc::now().to_rfc3339();
+    wake_satisfied_sessions(storage, &now)?;
+    if let Some(active_timeout) = active_timeout {
+        recover_stale_active_sessions(storage, &now, active_timeout)?;
+    }
+    retry_failed_sessions(storage, &now)?;
+
+    match activate_one_pending(storage, &now)? {
+        Some(session) => {
+            let children_results = match &session.trigger {
+                Some(trigger_json) => collect_children_results(storage, trigger_json)?,
+                None => None,
+            };
+
+            if let Some(results) = &children_results {
+                storage.insert_event(&Event::new(
+                    "results_aggregated",
+                    Some(&session.id),
+                    Some(serde_json::to_string(results)?),
+                ))?;
+            }
+
+            // Output session info for the caller to spawn an agent
+            let mut output = serde_json::json!({
+                "action": "spawn",
+                "session": {
+                    "id": session.id,
+                    "ken": session.ken,
+                    "task": session.task,
+                    "checkpoint": session.checkpoint,
+                }
+            });
+            if let Some(results) = &children_results {
+                output["session"]["children_results"] = serde_json::to_value(results)?;
+            }
+            println!("{}", serde_json::to_string(&output)?);
+        }
+        None => println!("{{\"action\":\"none\"}}"),
+    }
+
+    Ok(())
+}
 
-    // First, check sleeping sessions for satisfied triggers
+/// If `trigger_json` is an `AllComplete`/`AnyComplete` trigger, gather the
+/// listed children's `result` fields so a newly-woken parent can see what its
+/// children produced, not just its own saved `checkpoint`.
+pub(crate) fn collect_children_results(storage: &Storage, trigger_json: &str) -> Result<Option<Vec<ChildResult>>> {
+    let ids = match Trigger::from_json(trigger_json) {
+        Ok(Trigger::AllComplete(ids)) | Ok(Trigger::AnyComplete(ids)) => ids,
+        _ => return Ok(None),
+    };
+
+    let ids = if ids.len() == 1 {
+        OneOrVec::One(ids.into_iter().next().expect("len == 1"))
+    } else {
+        OneOrVec::Vec(ids)
+    };
+
+    let results = ids
+        .into_vec()
+        .into_iter()
+        .map(|id| {
+            let result = storage.get_session(&id).ok().and_then(|s| s.result);
+            ChildResult { id, result }
+        })
+        .collect();
+
+    Ok(Some(results))
+}
+
+/// Like `run_with_storage`, but instead of printing a `spawn` line for the
+/// caller to act on, runs the activated session's agent itself via the
+/// `executor` module and records the outcome directly.
+pub fn run_with_storage_executing(
+    storage: &Storage,
+    config: &KenConfig,
+    active_timeout: Option<Duration>,
+) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    wake_satisfied_sessions(storage, &now)?;
+    if let Some(active_timeout) = active_timeout {
+        recover_stale_active_sessions(storage, &now, active_timeout)?;
+    }
+    retry_failed_sessions(storage, &now)?;
+
+    if let Some(session) = activate_one_pending(storage, &now)? {
+        let children_results = match &session.trigger {
+            Some(trigger_json) => collect_children_results(storage, trigger_json)?,
+            None => None,
+        };
+        if let Some(results) = &children_results {
+            storage.insert_event(&Event::new(
+                "results_aggregated",
+                Some(&session.id),
+                Some(serde_json::to_string(results)?),
+            ))?;
+        }
+        executor::execute_session(storage, config, &session, children_results.as_deref())?;
+    }
+
+    Ok(())
+}
+
+/// Base retry delay in seconds; the actual delay grows exponentially as
+/// `base * 2^retry_count`, capped at `MAX_RETRY_DELAY_SECONDS`.
+const RETRY_BASE_DELAY_SECONDS: u64 = 30;
+const MAX_RETRY_DELAY_SECONDS: u64 = 3600;
+
+/// Give `Failed` sessions with retries remaining another chance: put them back
+/// to `Sleeping` behind a `TimeoutSeconds` backoff trigger instead of leaving
+/// them `Failed` for good. A session only stays `Failed` once `retry_count`
+/// reaches `max_retries`.
+pub(crate) fn retry_failed_sessions(storage: &Storage, now: &str) -> Result<()> {
+    let failed = storage.get_sessions_by_status(SessionStatus::Failed)?;
+    for session in failed {
+        if session.retry_count >= session.max_retries {
+            continue;
+        }
+
+        let delay = RETRY_BASE_DELAY_SECONDS
+            .saturating_mul(1u64 << session.retry_count.min(20))
+            .min(MAX_RETRY_DELAY_SECONDS);
+        let trigger_json = serde_json::to_string(&Trigger::TimeoutSeconds(delay))?;
+        let next_retry_count = session.retry_count + 1;
+
+        let retried = storage.retry_session(&session.id, &trigger_json, next_retry_count, now)?;
+
+        if retried {
+            storage.insert_event(&Event::new(
+                "session_retry",
+                Some(&session.id),
+                Some(format!(
+                    "retry {}/{}, backing off {}s",
+                    next_retry_count, session.max_retries, delay
+                )),
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Scan `Active` sessions for ones whose agent has gone silent (no heartbeat,
+/// completion, or activation update in longer than `active_timeout`) and fail
+/// them out, so a crashed agent can't block the rest of a workflow forever.
+pub(crate) fn recover_stale_active_sessions(
+    storage: &Storage,
+    now: &str,
+    active_timeout: Duration,
+) -> Result<()> {
+    let active = storage.get_sessions_by_status(SessionStatus::Active)?;
+    for session in active {
+        let last_seen = match DateTime::parse_from_rfc3339(&session.updated_at) {
+            Ok(ts) => ts.with_timezone(&Utc),
+            Err(_) => continue,
+        };
+        let elapsed = Utc::now() - last_seen;
+        if elapsed.num_seconds() < active_timeout.as_secs() as i64 {
+            continue;
+        }
+
+        // Atomically fail this session (only if still active - it may have
+        // completed or heartbeated in the meantime).
+        let stale = storage.try_update_session_status(
+            &session.id,
+            SessionStatus::Active,
+            SessionStatus::Failed,
+            now,
+        )?;
+
+        if stale {
+            storage.fail_session(&session.id, "session went stale: no heartbeat from agent", now)?;
+            storage.insert_event(&Event::new(
+                "session_stale",
+                Some(&session.id),
+                Some(format!("no update in {}s (timeout: {}s)", elapsed.num_seconds(), active_timeout.as_secs())),
+            ))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Atomically activate the first still-pending session found, returning it.
+/// Returns `None` once no pending session could be claimed.
+fn activate_one_pending(storage: &Storage, now: &str) -> Result<Option<Session>> {
+    let pending = storage.get_sessions_by_status(SessionStatus::Pending)?;
+    for session in pending {
+        let activated = storage.try_update_session_status(
+            &session.id,
+            SessionStatus::Pending,
+            SessionStatus::Active,
+            now,
+        )?;
+
+        if activated {
+            storage.insert_event(&Event::new("session_activated", Some(&session.id), None))?;
+            return Ok(Some(session));
+        }
+        // If activation failed (race condition), try the next pending session
+    }
+
+    Ok(None)
+}
+
+/// Wake any sleeping sessions whose trigger is now satisfied, putting them back
+/// to `Pending` so the activation pass below (or a future `process`/`daemon`
+/// tick) can pick them up. Shared by `run_with_storage` and the daemon loop.
+pub(crate) fn wake_satisfied_sessions(storage: &Storage, now: &str) -> Result<()> {
     let sleeping = storage.get_sessions_by_status(SessionStatus::Sleeping)?;
     for session in sleeping {
         if let Some(trigger_json) = &session.trigger {
@@ -30,7 +245,7 @@ pub fn run_with_storage(storage: &Storage) -> Result<()> {
                             &session.id,
                             SessionStatus::Sleeping,
                             SessionStatus::Pending,
-                            &now,
+                            now,
                         )?;
                         if woke {
                             storage.insert_event(&Event::new(
@@ -39,6 +254,22 @@ pub fn run_with_storage(storage: &Storage) -> Result<()> {
                                 session.trigger.clone(),
                             ))?;
                             println!("Woke session {} (trigger satisfied)", session.id);
+
+                            // Recurring schedules re-arm themselves so the next
+                            // `process`/`daemon` tick measures from this fire.
+                            if let Trigger::Schedule { cron, .. } = &trigger {
+                                let rearmed = Trigger::Schedule {
+                                    cron: cron.clone(),
+                                    last_fired: Some(now.to_string()),
+                                };
+                                let rearmed_json = serde_json::to_string(&rearmed)?;
+                                storage.update_session_trigger(&session.id, &rearmed_json, now)?;
+                                storage.insert_event(&Event::new(
+                                    "trigger_rearmed",
+                                    Some(&session.id),
+                                    Some(rearmed_json),
+                                ))?;
+                            }
                         }
                     }
                 }
@@ -55,42 +286,6 @@ pub fn run_with_storage(storage: &Storage) -> Result<()> {
         }
     }
 
-    // Then, find one pending session to activate
-    let pending = storage.get_sessions_by_status(SessionStatus::Pending)?;
-    for session in &pending {
-        // Atomically try to activate (only if still pending)
-        let activated = storage.try_update_session_status(
-            &session.id,
-            SessionStatus::Pending,
-            SessionStatus::Active,
-            &now,
-        )?;
-
-        if activated {
-            storage.insert_event(&Event::new(
-                "session_activated",
-                Some(&session.id),
-                None,
-            ))?;
-
-            // Output session info for the caller to spawn an agent
-            let output = serde_json::json!({
-                "action": "spawn",
-                "session": {
-                    "id": session.id,
-                    "ken": session.ken,
-                    "task": session.task,
-                    "checkpoint": session.checkpoint,
-                }
-            });
-            println!("{}", serde_json::to_string(&output)?);
-            return Ok(());
-        }
-        // If activation failed (race condition), try the next pending session
-    }
-
-    // No pending sessions could be activated
-    println!("{{\"action\":\"none\"}}");
     Ok(())
 }
 
@@ -126,7 +321,7 @@ mod tests {
         let session = Session::with_id("test-123", "test/ken", "do something", None);
         storage.insert_session(&session).unwrap();
 
-        let result = run_with_storage(&storage);
+        let result = run_with_storage(&storage, None);
 
         assert!(result.is_ok());
 
@@ -151,7 +346,7 @@ mod tests {
         parent.checkpoint = Some("saved state".to_string());
         storage.insert_session(&parent).unwrap();
 
-        let result = run_with_storage(&storage);
+        let result = run_with_storage(&storage, None);
 
         assert!(result.is_ok());
 
@@ -176,7 +371,7 @@ mod tests {
         parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
         storage.insert_session(&parent).unwrap();
 
-        let result = run_with_storage(&storage);
+        let result = run_with_storage(&storage, None);
 
         assert!(result.is_ok());
 
@@ -185,6 +380,134 @@ mod tests {
         assert_eq!(updated.status, SessionStatus::Sleeping);
     }
 
+    #[test]
+    fn test_process_wakes_and_rearms_schedule_trigger() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("sched-1", "heartbeat/ken", "ping", None);
+        session.status = SessionStatus::Sleeping;
+        session.trigger = Some(
+            r#"{"schedule":{"cron":"* * * * *","last_fired":null}}"#.to_string(),
+        );
+        session.updated_at = (Utc::now() - chrono::Duration::minutes(5)).to_rfc3339();
+        storage.insert_session(&session).unwrap();
+
+        let result = run_with_storage(&storage, None);
+        assert!(result.is_ok());
+
+        // Woken (and immediately re-activated by the same tick).
+        let updated = storage.get_session("sched-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Active);
+
+        // Trigger was re-armed with a fresh last_fired rather than left stale.
+        let trigger_json = updated.trigger.unwrap();
+        assert!(trigger_json.contains("last_fired"));
+        assert!(!trigger_json.contains("null"));
+    }
+
+    #[test]
+    fn test_collect_children_results_gathers_all_complete_children() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child1 = Session::with_id("child-1", "child/ken", "task1", None);
+        child1.status = SessionStatus::Complete;
+        child1.result = Some("result-1".to_string());
+        storage.insert_session(&child1).unwrap();
+
+        let mut child2 = Session::with_id("child-2", "child/ken", "task2", None);
+        child2.status = SessionStatus::Complete;
+        child2.result = Some("result-2".to_string());
+        storage.insert_session(&child2).unwrap();
+
+        let results = collect_children_results(
+            &storage,
+            r#"{"all_complete":["child-1","child-2"]}"#,
+        )
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "child-1");
+        assert_eq!(results[0].result, Some("result-1".to_string()));
+        assert_eq!(results[1].result, Some("result-2".to_string()));
+    }
+
+    #[test]
+    fn test_collect_children_results_none_for_timeout_trigger() {
+        let (_dir, storage) = create_test_storage();
+
+        let results = collect_children_results(&storage, r#"{"timeout_seconds":60}"#).unwrap();
+
+        assert!(results.is_none());
+    }
+
+    #[test]
+    fn test_recover_stale_active_sessions_fails_dead_agent() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("stale-1", "test/ken", "task", None);
+        session.status = SessionStatus::Active;
+        session.updated_at = (Utc::now() - chrono::Duration::minutes(10)).to_rfc3339();
+        storage.insert_session(&session).unwrap();
+
+        let result = run_with_storage(&storage, Some(std::time::Duration::from_secs(60)));
+        assert!(result.is_ok());
+
+        let updated = storage.get_session("stale-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Failed);
+        assert!(updated.result.is_some());
+    }
+
+    #[test]
+    fn test_recover_stale_active_sessions_spares_recent_heartbeat() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("fresh-1", "test/ken", "task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        let result = run_with_storage(&storage, Some(std::time::Duration::from_secs(300)));
+        assert!(result.is_ok());
+
+        let updated = storage.get_session("fresh-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_retry_failed_sessions_resleeps_with_backoff_when_retries_remain() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("failed-1", "test/ken", "task", None);
+        session.status = SessionStatus::Failed;
+        storage.insert_session(&session).unwrap();
+
+        let now = Utc::now().to_rfc3339();
+        retry_failed_sessions(&storage, &now).unwrap();
+
+        let updated = storage.get_session("failed-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Sleeping);
+        assert_eq!(updated.retry_count, 1);
+        assert!(updated.trigger.unwrap().contains("timeout_seconds"));
+    }
+
+    #[test]
+    fn test_retry_failed_sessions_leaves_exhausted_session_failed() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("failed-1", "test/ken", "task", None);
+        session.status = SessionStatus::Failed;
+        session.retry_count = 3;
+        session.max_retries = 3;
+        storage.insert_session(&session).unwrap();
+
+        let now = Utc::now().to_rfc3339();
+        retry_failed_sessions(&storage, &now).unwrap();
+
+        let updated = storage.get_session("failed-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Failed);
+        assert_eq!(updated.retry_count, 3);
+    }
+
     #[test]
     fn test_has_work_returns_true_with_pending() {
         let (_dir, storage) = create_test_storage();
@@ -214,7 +537,7 @@ mod tests {
     fn test_process_outputs_none_when_no_work() {
         let (_dir, storage) = create_test_storage();
 
-        let result = run_with_storage(&storage);
+        let result = run_with_storage(&storage, None);
 
         assert!(result.is_ok());
     }
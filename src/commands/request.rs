@@ -1,7 +1,8 @@
 use chrono::Utc;
 use crate::error::{KenError, Result};
-use crate::session::{AgentRequest, AgentResponse, Session, SessionStatus, Event};
+use crate::session::{AgentRequest, AgentResponse, Session, SessionStatus};
 use crate::storage::{open_storage, Storage};
+use crate::wake_engine::WakeEngine;
 
 /// Run the request command - process an agent request
 pub fn run(json: &str) -> Result<()> {
@@ -15,8 +16,10 @@ pub fn run(json: &str) -> Result<()> {
     Ok(())
 }
 
-/// Handle an agent request and return a response (with injected storage for testing)
-fn handle_request_with_storage(storage: &Storage, request: AgentRequest) -> Result<AgentResponse> {
+/// Handle an agent request and return a response (with injected storage for testing).
+/// Also reused by the `/request` HTTP endpoint so the CLI and server share one
+/// code path for applying an `AgentRequest`.
+pub(crate) fn handle_request_with_storage(storage: &Storage, request: AgentRequest) -> Result<AgentResponse> {
     let now = Utc::now().to_rfc3339();
 
     match request {
@@ -31,15 +34,13 @@ fn handle_request_with_storage(storage: &Storage, request: AgentRequest) -> Resu
                 )));
             }
 
-            // Complete the session
+            // Complete the session (this logs the `session_completed` event
+            // itself, alongside the row update)
             storage.complete_session(&session_id, &result, &now)?;
 
-            // Log event
-            storage.insert_event(&Event::new(
-                "session_completed",
-                Some(&session_id),
-                Some(result),
-            ))?;
+            // Try waking a sleeping parent right away instead of waiting for
+            // the next `process`/`daemon` poll tick to notice.
+            WakeEngine::new().on_terminal_transition(storage, &session_id, &now)?;
 
             Ok(AgentResponse::success(None))
         }
@@ -92,15 +93,25 @@ fn handle_request_with_storage(storage: &Storage, request: AgentRequest) -> Resu
 
             let trigger_str = serde_json::to_string(&trigger)?;
 
-            // Put session to sleep
+            // Put session to sleep (this logs the `session_sleeping` event
+            // itself, alongside the row update)
             storage.sleep_session(&session_id, &trigger_str, &checkpoint, &now)?;
 
-            // Log event
-            storage.insert_event(&Event::new(
-                "session_sleeping",
-                Some(&session_id),
-                Some(trigger_str),
-            ))?;
+            Ok(AgentResponse::success(None))
+        }
+
+        AgentRequest::Heartbeat { session_id } => {
+            // Verify session exists and is active
+            let session = storage.get_session(&session_id)?;
+            if session.status != SessionStatus::Active {
+                return Ok(AgentResponse::error(&format!(
+                    "Session {} is not active (status: {})",
+                    session_id,
+                    session.status.as_str()
+                )));
+            }
+
+            storage.touch_session(&session_id, &now)?;
 
             Ok(AgentResponse::success(None))
         }
@@ -160,6 +171,66 @@ mod tests {
         assert_eq!(updated.result, Some("all done".to_string()));
     }
 
+    #[test]
+    fn test_handle_complete_wakes_sleeping_parent_immediately() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "do something", None);
+        child.status = SessionStatus::Active;
+        child.parent_id = Some("parent-1".to_string());
+        storage.insert_session(&child).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "parent task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let request = AgentRequest::Complete {
+            session_id: "child-1".to_string(),
+            result: "done".to_string(),
+        };
+
+        let response = handle_request_with_storage(&storage, request).unwrap();
+        assert!(response.ok);
+
+        // Woken straight to `Pending`, not `Active`: the next `process`/
+        // `daemon` activation pass is the one that flips it to `Active` and
+        // emits the spawn/execute signal, so nobody is left owning an
+        // `Active` session with no one told to run its agent.
+        let updated_parent = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated_parent.status, SessionStatus::Pending);
+        assert!(updated_parent.trigger.is_some());
+    }
+
+    #[test]
+    fn test_handle_complete_woken_parent_is_activated_by_next_process_tick() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "do something", None);
+        child.status = SessionStatus::Active;
+        child.parent_id = Some("parent-1".to_string());
+        storage.insert_session(&child).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "parent task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let request = AgentRequest::Complete {
+            session_id: "child-1".to_string(),
+            result: "done".to_string(),
+        };
+        handle_request_with_storage(&storage, request).unwrap();
+
+        // The immediate wake only promotes the parent to `Pending`; it's the
+        // next `process` tick that actually activates it and would emit the
+        // `spawn`/execute signal for its agent.
+        crate::commands::process::run_with_storage(&storage, None).unwrap();
+
+        let updated_parent = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated_parent.status, SessionStatus::Active);
+    }
+
     #[test]
     fn test_handle_complete_fails_if_not_active() {
         let (_dir, storage) = create_test_storage();
@@ -242,6 +313,37 @@ mod tests {
         assert_eq!(updated.checkpoint, Some("waiting for timeout".to_string()));
     }
 
+    #[test]
+    fn test_handle_heartbeat_touches_active_session() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut session = Session::with_id("test-123", "test/ken", "do something", None);
+        session.status = SessionStatus::Active;
+        session.updated_at = "2020-01-01T00:00:00+00:00".to_string();
+        storage.insert_session(&session).unwrap();
+
+        let request = AgentRequest::Heartbeat { session_id: "test-123".to_string() };
+        let response = handle_request_with_storage(&storage, request).unwrap();
+
+        assert!(response.ok);
+        let updated = storage.get_session("test-123").unwrap();
+        assert_ne!(updated.updated_at, "2020-01-01T00:00:00+00:00");
+        assert_eq!(updated.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_handle_heartbeat_fails_if_not_active() {
+        let (_dir, storage) = create_test_storage();
+
+        let session = Session::with_id("test-123", "test/ken", "do something", None);
+        storage.insert_session(&session).unwrap();
+
+        let request = AgentRequest::Heartbeat { session_id: "test-123".to_string() };
+        let response = handle_request_with_storage(&storage, request).unwrap();
+
+        assert!(!response.ok);
+    }
+
     #[test]
     fn test_resolve_trigger_replaces_children() {
         let trigger = serde_json::json!({"all_complete": "__CHILDREN__"});
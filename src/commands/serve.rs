@@ -0,0 +1,324 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use crate::commands::{request, wake};
+use crate::error::Result;
+use crate::session::{AgentRequest, AgentResponse, SessionStatus};
+use crate::storage::{open_storage, Storage};
+
+/// Run the HTTP API server - serves session status and control over the
+/// network so external dashboards/agents don't need to spawn `ken` per call.
+pub fn run(bind: &str) -> Result<()> {
+    let storage = open_storage()?;
+    let listener = TcpListener::bind(bind)?;
+    println!("Listening on {}", bind);
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        if let Err(e) = handle_connection(&storage, stream) {
+            eprintln!("Error handling request: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_connection(storage: &Storage, mut stream: TcpStream) -> Result<()> {
+    let (method, path, body) = read_request(&stream)?;
+    let (status, body) = route(storage, &method, &path, &body);
+    write_response(&mut stream, status, &body)
+}
+
+/// Parse a minimal HTTP/1.1 request off the wire: request line, headers (only
+/// `Content-Length` matters), and body.
+fn read_request(stream: &TcpStream) -> Result<(String, String, String)> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let path = parts.next().unwrap_or("/").to_string();
+
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line == "\r\n" {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    if content_length > 0 {
+        reader.read_exact(&mut body)?;
+    }
+
+    Ok((method, path, String::from_utf8_lossy(&body).to_string()))
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &str) -> Result<()> {
+    let status_text = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        _ => "Internal Server Error",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        status_text,
+        body.len(),
+        body,
+    );
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+/// Route a parsed request to a handler and serialize its result, all without
+/// touching a socket - this is the part unit tests exercise directly.
+fn route(storage: &Storage, method: &str, path: &str, body: &str) -> (u16, String) {
+    let (path, query) = match path.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path, None),
+    };
+
+    match (method, path) {
+        ("GET", "/sessions") => list_sessions(storage, query),
+        ("GET", path) if path.starts_with("/sessions/") => {
+            get_session(storage, &path["/sessions/".len()..])
+        }
+        ("GET", "/events") => list_events_since(storage, query),
+        ("POST", "/request") => submit_request(storage, body),
+        ("POST", "/process") => trigger_process(storage),
+        ("POST", "/wake") => submit_wake(storage, body),
+        _ => respond_error(404, "not found"),
+    }
+}
+
+fn respond_error(status: u16, message: &str) -> (u16, String) {
+    let body = serde_json::to_string(&AgentResponse::error(message)).unwrap_or_default();
+    (status, body)
+}
+
+fn respond_ok(data: serde_json::Value) -> (u16, String) {
+    let body = serde_json::to_string(&AgentResponse::success(Some(data))).unwrap_or_default();
+    (200, body)
+}
+
+fn list_sessions(storage: &Storage, query: Option<&str>) -> (u16, String) {
+    let status_filter = query
+        .and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("status=")));
+
+    let sessions = match status_filter {
+        Some(status) => storage.get_sessions_by_status(SessionStatus::from_str(status)),
+        None => storage.get_all_sessions(),
+    };
+
+    match sessions {
+        Ok(sessions) => respond_ok(serde_json::json!({ "sessions": sessions })),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+fn get_session(storage: &Storage, id: &str) -> (u16, String) {
+    let session = match storage.get_session(id) {
+        Ok(session) => session,
+        Err(_) => return respond_error(404, &format!("session not found: {}", id)),
+    };
+
+    match storage.get_events(id) {
+        Ok(events) => respond_ok(serde_json::json!({ "session": session, "events": events })),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+fn list_events_since(storage: &Storage, query: Option<&str>) -> (u16, String) {
+    let since = query.and_then(|q| q.split('&').find_map(|kv| kv.strip_prefix("since=")));
+    let since = match since {
+        Some(since) => since,
+        None => return respond_error(400, "missing required query param: since"),
+    };
+
+    match storage.get_events_since(since) {
+        Ok(events) => respond_ok(serde_json::json!({ "events": events })),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+fn submit_request(storage: &Storage, body: &str) -> (u16, String) {
+    let agent_request: AgentRequest = match serde_json::from_str(body) {
+        Ok(req) => req,
+        Err(e) => return respond_error(400, &format!("invalid request body: {}", e)),
+    };
+
+    match request::handle_request_with_storage(storage, agent_request) {
+        Ok(response) => (200, serde_json::to_string(&response).unwrap_or_default()),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+fn trigger_process(storage: &Storage) -> (u16, String) {
+    match crate::commands::process::run_with_storage(storage, None) {
+        Ok(()) => respond_ok(serde_json::json!({})),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+fn submit_wake(storage: &Storage, body: &str) -> (u16, String) {
+    #[derive(serde::Deserialize)]
+    struct WakeBody {
+        ken: String,
+        task: String,
+    }
+
+    let wake_body: WakeBody = match serde_json::from_str(body) {
+        Ok(body) => body,
+        Err(e) => return respond_error(400, &format!("invalid wake body: {}", e)),
+    };
+
+    match wake::create_session(storage, &wake_body.ken, &wake_body.task) {
+        Ok(session) => respond_ok(serde_json::json!({ "id": session.id })),
+        Err(e) => respond_error(500, &e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use tempfile::tempdir;
+
+    fn create_test_storage() -> (tempfile::TempDir, Storage) {
+        let dir = tempdir().unwrap();
+        let ken_dir = dir.path().join(".ken");
+        std::fs::create_dir(&ken_dir).unwrap();
+        let db_path = ken_dir.join("ken.db");
+        let storage = Storage::create(&db_path).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn test_route_lists_all_sessions() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("s1", "test/ken", "task", None)).unwrap();
+
+        let (status, body) = route(&storage, "GET", "/sessions", "");
+
+        assert_eq!(status, 200);
+        assert!(body.contains("\"s1\""));
+    }
+
+    #[test]
+    fn test_route_filters_sessions_by_status() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("s1", "test/ken", "task", None)).unwrap();
+        let mut active = Session::with_id("s2", "test/ken", "task", None);
+        active.status = SessionStatus::Active;
+        storage.insert_session(&active).unwrap();
+
+        let (status, body) = route(&storage, "GET", "/sessions?status=active", "");
+
+        assert_eq!(status, 200);
+        assert!(body.contains("\"s2\""));
+        assert!(!body.contains("\"s1\""));
+    }
+
+    #[test]
+    fn test_route_gets_session_with_events() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("s1", "test/ken", "task", None)).unwrap();
+        storage.insert_event(&crate::session::Event::new("session_created", Some("s1"), None)).unwrap();
+
+        let (status, body) = route(&storage, "GET", "/sessions/s1", "");
+
+        assert_eq!(status, 200);
+        assert!(body.contains("session_created"));
+    }
+
+    #[test]
+    fn test_route_get_session_not_found() {
+        let (_dir, storage) = create_test_storage();
+
+        let (status, _body) = route(&storage, "GET", "/sessions/missing", "");
+
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn test_route_submits_agent_request() {
+        let (_dir, storage) = create_test_storage();
+        let mut session = Session::with_id("s1", "test/ken", "task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        let body = r#"{"type":"complete","session_id":"s1","result":"done"}"#;
+        let (status, resp_body) = route(&storage, "POST", "/request", body);
+
+        assert_eq!(status, 200);
+        assert!(resp_body.contains("\"ok\":true"));
+
+        let updated = storage.get_session("s1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Complete);
+    }
+
+    #[test]
+    fn test_route_wake_creates_session() {
+        let (_dir, storage) = create_test_storage();
+
+        let body = r#"{"ken":"test/ken","task":"do something"}"#;
+        let (status, resp_body) = route(&storage, "POST", "/wake", body);
+
+        assert_eq!(status, 200);
+        assert!(resp_body.contains("\"ok\":true"));
+
+        let sessions = storage.get_all_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].ken, "test/ken");
+    }
+
+    #[test]
+    fn test_route_process_triggers_tick() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("s1", "test/ken", "task", None)).unwrap();
+
+        let (status, _body) = route(&storage, "POST", "/process", "");
+
+        assert_eq!(status, 200);
+        let updated = storage.get_session("s1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_route_lists_events_since() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("s1", "test/ken", "task", None)).unwrap();
+        storage.insert_event(&crate::session::Event::new("session_created", Some("s1"), None)).unwrap();
+
+        let (status, body) = route(&storage, "GET", "/events?since=1970-01-01T00:00:00Z", "");
+
+        assert_eq!(status, 200);
+        assert!(body.contains("session_created"));
+    }
+
+    #[test]
+    fn test_route_events_requires_since_param() {
+        let (_dir, storage) = create_test_storage();
+
+        let (status, _body) = route(&storage, "GET", "/events", "");
+
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_route_unknown_path_returns_404() {
+        let (_dir, storage) = create_test_storage();
+
+        let (status, _body) = route(&storage, "GET", "/nonexistent", "");
+
+        assert_eq!(status, 404);
+    }
+}
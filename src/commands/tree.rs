@@ -0,0 +1,84 @@
+use crate::error::Result;
+use crate::storage::{open_storage, Storage};
+
+/// Run the tree command - show `id`'s ancestors and its full subtree
+pub fn run(id: &str) -> Result<()> {
+    let storage = open_storage()?;
+    run_with_storage(&storage, id)
+}
+
+/// Tree command implementation with injected storage (for testing)
+pub fn run_with_storage(storage: &Storage, id: &str) -> Result<()> {
+    let root = storage.get_session(id)?;
+
+    let ancestors = storage.get_ancestors(id)?;
+    if !ancestors.is_empty() {
+        println!("Ancestors:");
+        for ancestor in ancestors.iter().rev() {
+            println!("  {} - {} ({})", ancestor.id, ancestor.task, ancestor.status.as_str());
+        }
+        println!();
+    }
+
+    println!("{} - {} ({})", root.id, root.task, root.status.as_str());
+
+    let subtree = storage.get_subtree(id)?;
+    for node in &subtree {
+        let indent = "  ".repeat(node.depth as usize);
+        println!("{}{} - {} ({})", indent, node.session.id, node.session.task, node.session.status.as_str());
+    }
+
+    let open = storage.count_open_descendants(id)?;
+    println!();
+    println!("{} open descendant(s)", open);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use tempfile::tempdir;
+
+    fn create_test_storage() -> (tempfile::TempDir, Storage) {
+        let dir = tempdir().unwrap();
+        let ken_dir = dir.path().join(".ken");
+        std::fs::create_dir(&ken_dir).unwrap();
+        let db_path = ken_dir.join("ken.db");
+        let storage = Storage::create(&db_path).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn test_tree_with_no_relatives() {
+        let (_dir, storage) = create_test_storage();
+        storage.insert_session(&Session::with_id("root-1", "test/ken", "task", None)).unwrap();
+
+        let result = run_with_storage(&storage, "root-1");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_tree_with_ancestors_and_descendants() {
+        let (_dir, storage) = create_test_storage();
+
+        storage.insert_session(&Session::with_id("grandparent-1", "test/ken", "task", None)).unwrap();
+        storage.insert_session(&Session::with_id("parent-1", "test/ken", "task", Some("grandparent-1".to_string()))).unwrap();
+        storage.insert_session(&Session::with_id("child-1", "test/ken", "task", Some("parent-1".to_string()))).unwrap();
+
+        let result = run_with_storage(&storage, "parent-1");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_tree_unknown_session_errors() {
+        let (_dir, storage) = create_test_storage();
+
+        let result = run_with_storage(&storage, "missing");
+
+        assert!(result.is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::error::Result;
-use crate::session::{Session, Event};
+use crate::session::{Session, SessionStatus, Event};
 use crate::storage::{open_storage, Storage};
 
 /// Run the wake command - creates a new session and starts an agent
@@ -10,18 +10,23 @@ pub fn run(ken: &str, task: &str) -> Result<()> {
 
 /// Wake command implementation that accepts a storage instance (for testing)
 pub fn run_with_storage(storage: &Storage, ken: &str, task: &str) -> Result<()> {
-    // Create new session
+    let session = create_session(storage, ken, task)?;
+    println!("{}", session.id);
+    Ok(())
+}
+
+/// Create and persist a new pending session, logging its creation. Shared by
+/// the CLI `wake` command and the HTTP server's `/wake` endpoint.
+pub(crate) fn create_session(storage: &Storage, ken: &str, task: &str) -> Result<Session> {
     let mut session = Session::new(ken, task, None);
-    session.status = crate::session::SessionStatus::Pending;
+    session.status = SessionStatus::Pending;
 
     storage.insert_session(&session)?;
 
-    // Log event
     let event = Event::new("session_created", Some(&session.id), None);
     storage.insert_event(&event)?;
 
-    println!("{}", session.id);
-    Ok(())
+    Ok(session)
 }
 
 #[cfg(test)]
@@ -0,0 +1,69 @@
+use serde::Deserialize;
+use std::path::Path;
+use crate::error::{KenError, Result};
+
+/// Project-level configuration loaded from `ken.toml`, next to the `.ken/`
+/// directory. Every field is optional so a project can omit the file entirely.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct KenConfig {
+    /// Shell command used to launch an agent for a session, e.g. `"my-agent run"`.
+    /// The built-in executor (`ken process --execute` / `ken daemon --execute`)
+    /// appends `<ken> <task> <context>` as arguments when it spawns this, where
+    /// `<context>` is a JSON blob with the session's checkpoint and children's results.
+    pub agent_cmd: Option<String>,
+
+    /// Wall-clock seconds an agent may run before the executor kills it and
+    /// marks the session `Failed`. Unset means no timeout.
+    pub agent_timeout_seconds: Option<u64>,
+}
+
+impl KenConfig {
+    /// Load `ken.toml` from `project_root`. Returns the default (all-`None`)
+    /// config if the file doesn't exist.
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = project_root.join("ken.toml");
+        if !path.exists() {
+            return Ok(KenConfig::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        toml::from_str(&contents)
+            .map_err(|e| KenError::InvalidRequest(format!("invalid ken.toml: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_defaults_when_no_file() {
+        let dir = tempdir().unwrap();
+        let config = KenConfig::load(dir.path()).unwrap();
+        assert!(config.agent_cmd.is_none());
+        assert!(config.agent_timeout_seconds.is_none());
+    }
+
+    #[test]
+    fn test_load_parses_agent_cmd() {
+        let dir = tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("ken.toml"),
+            "agent_cmd = \"my-agent run\"\nagent_timeout_seconds = 300\n",
+        ).unwrap();
+
+        let config = KenConfig::load(dir.path()).unwrap();
+        assert_eq!(config.agent_cmd, Some("my-agent run".to_string()));
+        assert_eq!(config.agent_timeout_seconds, Some(300));
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_toml() {
+        let dir = tempdir().unwrap();
+        std::fs::write(dir.path().join("ken.toml"), "not = valid = toml").unwrap();
+
+        let result = KenConfig::load(dir.path());
+        assert!(result.is_err());
+    }
+}
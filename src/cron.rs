@@ -0,0 +1,131 @@
+use chrono::{DateTime, Datelike, Duration, Timelike, Utc};
+
+/// Minimal cron expression support backing `Trigger::Schedule`.
+///
+/// Supports the standard 5-field form `minute hour day-of-month month day-of-week`,
+/// where each field is `*`, a single number, a comma-separated list, or a `*/N`
+/// step. Ranges (`1-5`) and named months/weekdays are not supported - extend
+/// `Field::parse` here if a workflow needs them.
+struct Field {
+    values: Option<Vec<u32>>,
+}
+
+impl Field {
+    fn parse(s: &str, max: u32) -> Option<Field> {
+        if s == "*" {
+            return Some(Field { values: None });
+        }
+
+        if let Some(step) = s.strip_prefix("*/") {
+            let step: u32 = step.parse().ok()?;
+            if step == 0 {
+                return None;
+            }
+            return Some(Field { values: Some((0..=max).step_by(step as usize).collect()) });
+        }
+
+        let mut values = Vec::new();
+        for part in s.split(',') {
+            values.push(part.parse().ok()?);
+        }
+        Some(Field { values: Some(values) })
+    }
+
+    fn matches(&self, value: u32) -> bool {
+        match &self.values {
+            None => true,
+            Some(values) => values.contains(&value),
+        }
+    }
+}
+
+struct Schedule {
+    minute: Field,
+    hour: Field,
+    day_of_month: Field,
+    month: Field,
+    day_of_week: Field,
+}
+
+impl Schedule {
+    fn parse(expr: &str) -> Option<Schedule> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            return None;
+        }
+
+        Some(Schedule {
+            minute: Field::parse(fields[0], 59)?,
+            hour: Field::parse(fields[1], 23)?,
+            day_of_month: Field::parse(fields[2], 31)?,
+            month: Field::parse(fields[3], 12)?,
+            day_of_week: Field::parse(fields[4], 6)?,
+        })
+    }
+
+    fn matches(&self, dt: &DateTime<Utc>) -> bool {
+        self.minute.matches(dt.minute())
+            && self.hour.matches(dt.hour())
+            && self.day_of_month.matches(dt.day())
+            && self.month.matches(dt.month())
+            && self.day_of_week.matches(dt.weekday().num_days_from_sunday())
+    }
+}
+
+/// Compute the next minute strictly after `after` at which `expr` fires,
+/// searching minute-by-minute up to a year out. Returns `None` if `expr`
+/// doesn't parse, or no match falls within that range.
+pub fn next_fire_after(expr: &str, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let schedule = Schedule::parse(expr)?;
+
+    let mut candidate = (after + Duration::minutes(1)).with_second(0)?.with_nanosecond(0)?;
+    let limit = after + Duration::days(366);
+
+    while candidate <= limit {
+        if schedule.matches(&candidate) {
+            return Some(candidate);
+        }
+        candidate += Duration::minutes(1);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_wildcard_schedule_fires_next_minute() {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let next = next_fire_after("* * * * *", after).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 1, 1, 12, 1, 0).unwrap());
+    }
+
+    #[test]
+    fn test_specific_minute_schedule() {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let next = next_fire_after("30 * * * *", after).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap());
+    }
+
+    #[test]
+    fn test_step_schedule() {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 12, 5, 0).unwrap();
+        let next = next_fire_after("*/15 * * * *", after).unwrap();
+        assert_eq!(next, Utc.with_ymd_and_hms(2024, 1, 1, 12, 15, 0).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_expression_returns_none() {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(next_fire_after("not a cron", after).is_none());
+    }
+
+    #[test]
+    fn test_malformed_step_returns_none() {
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert!(next_fire_after("*/0 * * * *", after).is_none());
+    }
+}
@@ -22,6 +22,12 @@ pub enum KenError {
 
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
+
+    #[error("Database contended: {0}")]
+    Contended(String),
+
+    #[error("Database schema version {found} is newer than this binary supports (up to version {supported}); refusing to open, since downgrading could silently lose data")]
+    SchemaTooNew { found: i64, supported: i64 },
 }
 
 pub type Result<T> = std::result::Result<T, KenError>;
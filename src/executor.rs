@@ -0,0 +1,244 @@
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use crate::config::KenConfig;
+use crate::error::{KenError, Result};
+use crate::session::{ChildResult, Event, Session};
+use crate::storage::Storage;
+
+/// Run `session`'s agent to completion and record the outcome: `Complete`
+/// (with captured stdout as the result) on a zero exit, or `Failed` (with
+/// captured stderr, or a timeout note) otherwise. Every output line is also
+/// recorded as an `agent_output` event, once the agent has exited; nothing is
+/// recorded while it is still running.
+///
+/// `children_results`, when the caller has already aggregated them from a
+/// satisfied `AllComplete`/`AnyComplete` trigger, is forwarded to the agent
+/// alongside `session.checkpoint` so a resumed/woken agent run via
+/// `--execute` sees the same context as the non-`--execute` `spawn` JSON
+/// path does.
+pub fn execute_session(
+    storage: &Storage,
+    config: &KenConfig,
+    session: &Session,
+    children_results: Option<&[ChildResult]>,
+) -> Result<()> {
+    let storage_for_lines = storage;
+    let session_id = session.id.clone();
+    let context = serde_json::to_string(&serde_json::json!({
+        "checkpoint": session.checkpoint,
+        "children_results": children_results,
+    }))?;
+    let result = run_agent(config, &session.ken, &session.task, &context, |stream, line| {
+        let _ = storage_for_lines.insert_event(&Event::new(
+            "agent_output",
+            Some(&session_id),
+            Some(format!("[{}] {}", stream, line)),
+        ));
+    })?;
+
+    let now = Utc::now().to_rfc3339();
+
+    if result.succeeded() {
+        // Logs the `session_completed` event itself, alongside the row update
+        storage.complete_session(&session.id, &result.stdout, &now)?;
+    } else {
+        let error = if result.timed_out {
+            "agent timed out".to_string()
+        } else {
+            result.stderr.clone()
+        };
+        storage.fail_session(&session.id, &error, &now)?;
+        storage.insert_event(&Event::new("session_failed", Some(&session.id), Some(error)))?;
+    }
+
+    Ok(())
+}
+
+/// Which stream a captured line came from.
+enum StreamLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+/// Outcome of running an agent process to completion (or timeout).
+pub struct ExecResult {
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+}
+
+impl ExecResult {
+    pub fn succeeded(&self) -> bool {
+        !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+/// Spawn `config.agent_cmd` (appending `ken`, `task`, and a JSON `context`
+/// blob - `{"checkpoint":..., "children_results":...}` - as arguments),
+/// hand each stdout/stderr line to `on_line` once the child has exited (lines
+/// are not delivered while it is still running), and enforce
+/// `config.agent_timeout_seconds` (if set) by killing the child once exceeded.
+///
+/// This is the low-level primitive behind `ken process --execute` / `ken
+/// daemon --execute`: it only runs the process and captures output, leaving
+/// it to the caller to turn the result into a `Complete`/`Failed` transition.
+pub fn run_agent(
+    config: &KenConfig,
+    ken: &str,
+    task: &str,
+    context: &str,
+    mut on_line: impl FnMut(&str, &str),
+) -> Result<ExecResult> {
+    let agent_cmd = config.agent_cmd.as_ref().ok_or_else(|| {
+        KenError::InvalidRequest("no agent_cmd configured in ken.toml".to_string())
+    })?;
+
+    let mut parts = agent_cmd.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or_else(|| KenError::InvalidRequest("agent_cmd is empty".to_string()))?;
+    let args: Vec<&str> = parts.collect();
+
+    let mut child = Command::new(program)
+        .args(&args)
+        .arg(ken)
+        .arg(task)
+        .arg(context)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (tx, rx) = mpsc::channel::<StreamLine>();
+    let stdout_reader = spawn_line_reader(child.stdout.take(), tx.clone(), StreamLine::Stdout);
+    let stderr_reader = spawn_line_reader(child.stderr.take(), tx, StreamLine::Stderr);
+
+    let timeout = config.agent_timeout_seconds.map(Duration::from_secs);
+    let (exit_code, timed_out) = wait_with_timeout(&mut child, timeout)?;
+
+    // Let both readers hit EOF first so every line is in the channel, then
+    // drain what they produced while we were waiting/killing.
+    let _ = stdout_reader.join();
+    let _ = stderr_reader.join();
+
+    let mut stdout_lines = Vec::new();
+    let mut stderr_lines = Vec::new();
+    while let Ok(line) = rx.try_recv() {
+        match line {
+            StreamLine::Stdout(l) => {
+                on_line("stdout", &l);
+                stdout_lines.push(l);
+            }
+            StreamLine::Stderr(l) => {
+                on_line("stderr", &l);
+                stderr_lines.push(l);
+            }
+        }
+    }
+
+    Ok(ExecResult {
+        exit_code,
+        stdout: stdout_lines.join("\n"),
+        stderr: stderr_lines.join("\n"),
+        timed_out,
+    })
+}
+
+fn spawn_line_reader(
+    stream: Option<impl Read + Send + 'static>,
+    tx: mpsc::Sender<StreamLine>,
+    wrap: fn(String) -> StreamLine,
+) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let Some(stream) = stream else { return };
+        for line in BufReader::new(stream).lines().map_while(std::result::Result::ok) {
+            if tx.send(wrap(line)).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+/// Poll the child until it exits or `timeout` elapses, killing (and reaping)
+/// it in the timeout case.
+fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> Result<(Option<i32>, bool)> {
+    let start = Instant::now();
+
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok((status.code(), false));
+        }
+
+        if let Some(timeout) = timeout {
+            if start.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok((None, true));
+            }
+        }
+
+        thread::sleep(Duration::from_millis(25));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_cmd(cmd: &str) -> KenConfig {
+        KenConfig {
+            agent_cmd: Some(cmd.to_string()),
+            agent_timeout_seconds: None,
+        }
+    }
+
+    #[test]
+    fn test_run_agent_captures_stdout_on_success() {
+        let config = config_with_cmd("echo");
+        let mut lines = Vec::new();
+
+        let result = run_agent(&config, "hello", "world", "{}", |_stream, line| {
+            lines.push(line.to_string());
+        }).unwrap();
+
+        assert!(result.succeeded());
+        assert_eq!(result.stdout.trim(), "hello world {}");
+        assert!(!result.timed_out);
+    }
+
+    #[test]
+    fn test_run_agent_reports_nonzero_exit() {
+        let config = config_with_cmd("false");
+
+        let result = run_agent(&config, "ken", "task", "{}", |_, _| {}).unwrap();
+
+        assert!(!result.succeeded());
+        assert_eq!(result.exit_code, Some(1));
+        assert!(!result.timed_out);
+    }
+
+    #[test]
+    fn test_run_agent_enforces_timeout() {
+        let mut config = config_with_cmd("sleep");
+        config.agent_timeout_seconds = Some(0);
+
+        // `sleep 5 5 0` run with a 0-second budget should be killed almost immediately.
+        let result = run_agent(&config, "5", "5", "0", |_, _| {}).unwrap();
+
+        assert!(result.timed_out);
+        assert!(!result.succeeded());
+    }
+
+    #[test]
+    fn test_run_agent_requires_configured_command() {
+        let config = KenConfig::default();
+
+        let result = run_agent(&config, "ken", "task", "{}", |_, _| {});
+
+        assert!(result.is_err());
+    }
+}
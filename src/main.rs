@@ -1,9 +1,16 @@
 use clap::{Parser, Subcommand};
+use std::time::Duration;
 
 mod commands;
 mod storage;
 mod session;
 mod error;
+mod cron;
+mod config;
+mod executor;
+mod wake_engine;
+mod retry;
+mod migrations;
 
 use error::Result;
 
@@ -37,10 +44,70 @@ enum Commands {
     },
 
     /// Evaluate triggers and spawn one pending session
-    Process,
+    Process {
+        /// Run the activated session's agent directly via `agent_cmd` in
+        /// `ken.toml`, instead of printing a `spawn` line for the caller
+        #[arg(long)]
+        execute: bool,
+
+        /// Seconds an `Active` session may go without a heartbeat/update
+        /// before it's considered dead and marked `Failed`. Unset disables
+        /// this check.
+        #[arg(long)]
+        active_timeout: Option<u64>,
+    },
 
     /// Show current session status
     Status,
+
+    /// Run as a resident supervisor, continuously waking and activating sessions
+    Daemon {
+        /// Maximum number of sessions to keep active at once
+        #[arg(long, default_value_t = 1)]
+        max_concurrency: usize,
+
+        /// Seconds to sleep between ticks
+        #[arg(long, default_value_t = 1)]
+        poll_interval: u64,
+
+        /// Keep running even once there is no work left, instead of exiting
+        #[arg(long)]
+        watch: bool,
+
+        /// Run each activated session's agent directly via `agent_cmd` in
+        /// `ken.toml`, instead of printing a `spawn` line for the caller
+        #[arg(long)]
+        execute: bool,
+
+        /// Seconds an `Active` session may go without a heartbeat/update
+        /// before it's considered dead and marked `Failed`. Unset disables
+        /// this check.
+        #[arg(long)]
+        active_timeout: Option<u64>,
+    },
+
+    /// Run a small HTTP API server exposing session status and control
+    Serve {
+        /// Address to bind the server to
+        #[arg(long, default_value = "127.0.0.1:7878")]
+        bind: String,
+    },
+
+    /// Show a session's ancestors and its full descendant subtree
+    Tree {
+        /// Session id to center the tree on
+        id: String,
+    },
+
+    /// Reconstruct a session's state as of a point in time from its event log
+    History {
+        /// Session id to reconstruct
+        id: String,
+
+        /// RFC 3339 timestamp to reconstruct the session's state at
+        #[arg(long)]
+        at: String,
+    },
 }
 
 fn main() {
@@ -57,7 +124,21 @@ fn run() -> Result<()> {
         Commands::Init => commands::init::run(),
         Commands::Wake { ken, task } => commands::wake::run(&ken, &task),
         Commands::Request { json } => commands::request::run(&json),
-        Commands::Process => commands::process::run(),
+        Commands::Process { execute, active_timeout } => {
+            commands::process::run(execute, active_timeout.map(Duration::from_secs))
+        }
         Commands::Status => commands::status::run(),
+        Commands::Daemon { max_concurrency, poll_interval, watch, execute, active_timeout } => {
+            commands::daemon::run(
+                max_concurrency,
+                Duration::from_secs(poll_interval),
+                watch,
+                execute,
+                active_timeout.map(Duration::from_secs),
+            )
+        }
+        Commands::Serve { bind } => commands::serve::run(&bind),
+        Commands::Tree { id } => commands::tree::run(&id),
+        Commands::History { id, at } => commands::history::run(&id, &at),
     }
 }
@@ -0,0 +1,127 @@
+use rusqlite::Connection;
+use crate::error::{KenError, Result};
+
+/// One forward step in the schema's history, taking the database from
+/// `version - 1` to `version`. Tracked via SQLite's `PRAGMA user_version` so
+/// `Storage::open` can upgrade an existing `ken.db` in place and
+/// `Storage::create` can simply run every migration starting from version 0.
+struct Migration {
+    version: i64,
+    sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    sql: r#"
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    ken TEXT NOT NULL,
+    task TEXT NOT NULL,
+    status TEXT NOT NULL,
+    parent_id TEXT,
+    trigger TEXT,
+    checkpoint TEXT,
+    result TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    retry_count INTEGER NOT NULL DEFAULT 0,
+    max_retries INTEGER NOT NULL DEFAULT 3,
+    FOREIGN KEY (parent_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status);
+CREATE INDEX IF NOT EXISTS idx_sessions_parent ON sessions(parent_id);
+
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    ts TEXT NOT NULL,
+    session_id TEXT,
+    event_type TEXT NOT NULL,
+    data TEXT,
+    FOREIGN KEY (session_id) REFERENCES sessions(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_events_session ON events(session_id);
+CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
+"#,
+}];
+
+/// The schema version this binary understands. Bump alongside appending a
+/// new entry to `MIGRATIONS`.
+pub const CURRENT_VERSION: i64 = 1;
+
+/// Read `PRAGMA user_version` and run every migration above it, each in its
+/// own transaction, bumping `user_version` to match as it goes. Safe to call
+/// on a brand-new (all-zero) connection or an already up-to-date one - both
+/// just become no-ops past the last applicable step. Refuses to touch a
+/// database whose `user_version` is already newer than `CURRENT_VERSION`,
+/// since a binary this old has no idea what such a schema looks like.
+pub fn apply(conn: &Connection) -> Result<()> {
+    let mut version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if version > CURRENT_VERSION {
+        return Err(KenError::SchemaTooNew {
+            found: version,
+            supported: CURRENT_VERSION,
+        });
+    }
+
+    for migration in MIGRATIONS {
+        if migration.version <= version {
+            continue;
+        }
+
+        conn.execute("BEGIN", [])?;
+        let result = conn.execute_batch(migration.sql);
+        match result {
+            Ok(()) => {
+                conn.execute_batch(&format!("PRAGMA user_version = {}", migration.version))?;
+                conn.execute("COMMIT", [])?;
+                version = migration.version;
+            }
+            Err(e) => {
+                let _ = conn.execute("ROLLBACK", []);
+                return Err(e.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_brings_fresh_database_to_current_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply(&conn).unwrap();
+
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, CURRENT_VERSION);
+
+        // Tables from the version-1 migration should exist
+        conn.execute("INSERT INTO sessions (id, ken, task, status, created_at, updated_at) VALUES ('a', 'k', 't', 'pending', '0', '0')", []).unwrap();
+    }
+
+    #[test]
+    fn test_apply_is_idempotent_on_already_current_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply(&conn).unwrap();
+        apply(&conn).unwrap();
+
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_apply_rejects_database_newer_than_binary_supports() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!("PRAGMA user_version = {}", CURRENT_VERSION + 1)).unwrap();
+
+        let result = apply(&conn);
+        assert!(matches!(result, Err(KenError::SchemaTooNew { found, supported })
+            if found == CURRENT_VERSION + 1 && supported == CURRENT_VERSION));
+    }
+}
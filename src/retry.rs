@@ -0,0 +1,179 @@
+use std::time::Duration;
+use rusqlite::ErrorCode;
+use crate::error::{KenError, Result};
+
+/// Governs how `Storage` retries a statement or transaction body that failed
+/// with a transient `SQLITE_BUSY`/`SQLITE_LOCKED` error instead of surfacing
+/// it to the caller immediately.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub multiplier: f64,
+    /// Fraction of the current delay (0.0-1.0) to randomize by, so many
+    /// contending writers don't all wake up and retry in lockstep.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(20),
+            multiplier: 2.0,
+            jitter: 0.1,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries - useful for tests that want contention
+    /// errors to surface immediately.
+    pub fn no_retry() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            initial_delay: Duration::from_millis(0),
+            multiplier: 1.0,
+            jitter: 0.0,
+        }
+    }
+
+    /// Run `f`, retrying while it fails with a busy/locked database error, up
+    /// to `max_attempts` total tries with exponentially growing, jittered
+    /// delay between them. Once retries are exhausted, the contention error
+    /// is wrapped as `KenError::Contended`; any other error is returned as-is
+    /// on the first occurrence.
+    pub fn retry<T>(&self, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        let mut delay = self.initial_delay;
+
+        loop {
+            match f() {
+                Ok(value) => return Ok(value),
+                Err(KenError::Database(e)) if is_contended(&e) => {
+                    if attempt >= self.max_attempts {
+                        return Err(KenError::Contended(format!(
+                            "gave up after {} attempts: {}",
+                            attempt, e
+                        )));
+                    }
+
+                    std::thread::sleep(jittered(delay, self.jitter));
+                    delay = delay.mul_f64(self.multiplier);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Whether `err` represents a transient lock/contention condition (as opposed
+/// to, say, a constraint violation or a malformed query) that's worth retrying.
+pub(crate) fn is_contended(err: &rusqlite::Error) -> bool {
+    matches!(
+        err,
+        rusqlite::Error::SqliteFailure(ffi_err, _)
+            if matches!(ffi_err.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// Randomize `delay` by up to `jitter` fraction in either direction, so
+/// concurrent retriers spread out instead of colliding again immediately.
+fn jittered(delay: Duration, jitter: f64) -> Duration {
+    if jitter <= 0.0 {
+        return delay;
+    }
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let frac = (nanos % 1000) as f64 / 1000.0; // 0.0..1.0
+    let spread = delay.as_secs_f64() * jitter;
+    let offset = spread * (frac * 2.0 - 1.0); // +/- spread
+
+    Duration::from_secs_f64((delay.as_secs_f64() + offset).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_retry_succeeds_without_retrying_on_first_try() {
+        let policy = RetryPolicy::default();
+        let calls = Cell::new(0);
+
+        let result = policy.retry(|| {
+            calls.set(calls.get() + 1);
+            Ok::<_, KenError>(42)
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_retry_eventually_succeeds_after_transient_busy_errors() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(1),
+            multiplier: 1.0,
+            jitter: 0.0,
+        };
+        let calls = Cell::new(0);
+
+        let result = policy.retry(|| {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(KenError::Database(rusqlite::Error::SqliteFailure(
+                    rusqlite::ffi::Error::new(5), // SQLITE_BUSY
+                    None,
+                )))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_gives_up_and_returns_contended_error() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(1),
+            multiplier: 1.0,
+            jitter: 0.0,
+        };
+        let calls = Cell::new(0);
+
+        let result: Result<()> = policy.retry(|| {
+            calls.set(calls.get() + 1);
+            Err(KenError::Database(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(5), // SQLITE_BUSY
+                None,
+            )))
+        });
+
+        assert!(matches!(result, Err(KenError::Contended(_))));
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_retry_does_not_retry_non_contention_errors() {
+        let policy = RetryPolicy::default();
+        let calls = Cell::new(0);
+
+        let result: Result<()> = policy.retry(|| {
+            calls.set(calls.get() + 1);
+            Err(KenError::InvalidRequest("bad input".to_string()))
+        });
+
+        assert!(matches!(result, Err(KenError::InvalidRequest(_))));
+        assert_eq!(calls.get(), 1);
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 
 /// Session status
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,8 +49,14 @@ pub struct Session {
     pub result: Option<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub retry_count: u32,
+    pub max_retries: u32,
 }
 
+/// Default number of times a `Failed` session is retried with exponential
+/// backoff before being left `Failed` for good.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
 impl Session {
     /// Create a new session with a generated ID
     pub fn new(ken: &str, task: &str, parent_id: Option<String>) -> Self {
@@ -66,6 +72,8 @@ impl Session {
             result: None,
             created_at: now.clone(),
             updated_at: now,
+            retry_count: 0,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 
@@ -83,6 +91,8 @@ impl Session {
             result: None,
             created_at: now.clone(),
             updated_at: now,
+            retry_count: 0,
+            max_retries: DEFAULT_MAX_RETRIES,
         }
     }
 }
@@ -114,6 +124,18 @@ pub enum Trigger {
     AllComplete(Vec<String>),
     AnyComplete(Vec<String>),
     TimeoutSeconds(u64),
+    /// Recurring wake-up on a cron schedule. `last_fired` tracks the last time
+    /// this trigger fired (RFC3339), so the next fire time can be computed
+    /// relative to it; `None` means it has never fired yet.
+    Schedule {
+        cron: String,
+        last_fired: Option<String>,
+    },
+    /// Satisfied once at least `n` of `children` are `Complete`.
+    Count {
+        n: usize,
+        children: Vec<String>,
+    },
 }
 
 impl Trigger {
@@ -140,10 +162,47 @@ impl Trigger {
                     matches!(get_status(id), Some(SessionStatus::Complete))
                 })
             }
-            Trigger::TimeoutSeconds(_) => {
-                // TODO: implement timeout checking
-                false
+            Trigger::Count { n, children } => {
+                let done = children.iter()
+                    .filter(|id| matches!(get_status(id), Some(SessionStatus::Complete)))
+                    .count();
+                done >= *n
+            }
+            // Time-based triggers need `is_satisfied_with_time`; without a
+            // timestamp there's nothing to measure elapsed time against.
+            Trigger::TimeoutSeconds(_) | Trigger::Schedule { .. } => false,
+        }
+    }
+
+    /// Like `is_satisfied`, but also threads through the timestamp at which the
+    /// session entered `Sleeping`, for triggers (`TimeoutSeconds`, `Schedule`)
+    /// that need to measure elapsed wall-clock time rather than just child status.
+    pub fn is_satisfied_with_time(
+        &self,
+        get_status: impl Fn(&str) -> Option<SessionStatus>,
+        sleep_start: Option<&str>,
+    ) -> bool {
+        match self {
+            Trigger::TimeoutSeconds(timeout_secs) => {
+                let start = match sleep_start.and_then(|ts| DateTime::parse_from_rfc3339(ts).ok()) {
+                    Some(start) => start.with_timezone(&Utc),
+                    None => return false,
+                };
+                let elapsed = Utc::now() - start;
+                elapsed.num_seconds() >= *timeout_secs as i64
+            }
+            Trigger::Schedule { cron, last_fired } => {
+                let anchor = last_fired.as_deref().or(sleep_start);
+                let anchor = match anchor.and_then(|ts| DateTime::parse_from_rfc3339(ts).ok()) {
+                    Some(anchor) => anchor.with_timezone(&Utc),
+                    None => return false,
+                };
+                match crate::cron::next_fire_after(cron, anchor) {
+                    Some(next_fire) => Utc::now() >= next_fire,
+                    None => false,
+                }
             }
+            other => other.is_satisfied(get_status),
         }
     }
 }
@@ -167,6 +226,12 @@ pub enum AgentRequest {
         trigger: serde_json::Value,
         checkpoint: String,
     },
+    /// Sent periodically by a running agent to prove it's still alive. Bumps
+    /// `updated_at` so `process`/`daemon` don't mistake it for a stale, dead
+    /// `Active` session and fail it out from under the agent.
+    Heartbeat {
+        session_id: String,
+    },
 }
 
 /// Specification for a child session to spawn
@@ -176,6 +241,40 @@ pub struct ChildSpec {
     pub task: String,
 }
 
+/// One child's contribution to a woken parent's `children_results`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildResult {
+    pub id: String,
+    pub result: Option<String>,
+}
+
+/// One session in a `get_subtree` walk, annotated with how many `parent_id`
+/// hops separate it from the root (the root itself is depth 0).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionAtDepth {
+    pub session: Session,
+    pub depth: u32,
+}
+
+/// Normalizes a single item or a list of items into one uniform shape - used
+/// when building `children_results` so a trigger naming one child and a
+/// trigger naming many both flatten into the same array before being handed
+/// back to the agent.
+#[derive(Debug, Clone)]
+pub enum OneOrVec<T> {
+    One(T),
+    Vec(Vec<T>),
+}
+
+impl<T> OneOrVec<T> {
+    pub fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrVec::One(item) => vec![item],
+            OneOrVec::Vec(items) => items,
+        }
+    }
+}
+
 /// Response from ken to agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentResponse {
@@ -281,6 +380,109 @@ mod tests {
         assert!(satisfied);
     }
 
+    #[test]
+    fn test_trigger_count_satisfied_when_enough_children_complete() {
+        let trigger = Trigger::Count {
+            n: 2,
+            children: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+        };
+
+        let satisfied = trigger.is_satisfied(|id| {
+            match id {
+                "a" | "b" => Some(SessionStatus::Complete),
+                "c" => Some(SessionStatus::Active),
+                _ => None,
+            }
+        });
+        assert!(satisfied);
+    }
+
+    #[test]
+    fn test_trigger_count_not_satisfied_when_too_few_children_complete() {
+        let trigger = Trigger::Count {
+            n: 2,
+            children: vec!["a".to_string(), "b".to_string()],
+        };
+
+        let satisfied = trigger.is_satisfied(|id| {
+            match id {
+                "a" => Some(SessionStatus::Complete),
+                "b" => Some(SessionStatus::Active),
+                _ => None,
+            }
+        });
+        assert!(!satisfied);
+    }
+
+    #[test]
+    fn test_trigger_timeout_not_satisfied_before_elapsed() {
+        let trigger = Trigger::TimeoutSeconds(3600);
+        let sleep_start = Utc::now().to_rfc3339();
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, Some(&sleep_start));
+        assert!(!satisfied);
+    }
+
+    #[test]
+    fn test_trigger_timeout_satisfied_after_elapsed() {
+        let trigger = Trigger::TimeoutSeconds(60);
+        let sleep_start = (Utc::now() - chrono::Duration::seconds(120)).to_rfc3339();
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, Some(&sleep_start));
+        assert!(satisfied);
+    }
+
+    #[test]
+    fn test_trigger_timeout_not_satisfied_with_unparseable_timestamp() {
+        let trigger = Trigger::TimeoutSeconds(60);
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, Some("not-a-timestamp"));
+        assert!(!satisfied);
+    }
+
+    #[test]
+    fn test_trigger_schedule_not_satisfied_before_next_fire() {
+        let trigger = Trigger::Schedule {
+            cron: "* * * * *".to_string(),
+            last_fired: Some(Utc::now().to_rfc3339()),
+        };
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, None);
+        assert!(!satisfied);
+    }
+
+    #[test]
+    fn test_trigger_schedule_satisfied_once_due() {
+        let trigger = Trigger::Schedule {
+            cron: "* * * * *".to_string(),
+            last_fired: Some((Utc::now() - chrono::Duration::minutes(5)).to_rfc3339()),
+        };
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, None);
+        assert!(satisfied);
+    }
+
+    #[test]
+    fn test_trigger_schedule_falls_back_to_sleep_start_when_never_fired() {
+        let trigger = Trigger::Schedule {
+            cron: "* * * * *".to_string(),
+            last_fired: None,
+        };
+        let sleep_start = (Utc::now() - chrono::Duration::minutes(5)).to_rfc3339();
+
+        let satisfied = trigger.is_satisfied_with_time(|_| None, Some(&sleep_start));
+        assert!(satisfied);
+    }
+
+    #[test]
+    fn test_one_or_vec_flattens_both_variants() {
+        let one: OneOrVec<i32> = OneOrVec::One(1);
+        let many: OneOrVec<i32> = OneOrVec::Vec(vec![1, 2, 3]);
+
+        assert_eq!(one.into_vec(), vec![1]);
+        assert_eq!(many.into_vec(), vec![1, 2, 3]);
+    }
+
     #[test]
     fn test_agent_request_parse_complete() {
         let json = r#"{"type":"complete","session_id":"abc123","result":"done"}"#;
@@ -315,6 +517,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_agent_request_parse_heartbeat() {
+        let json = r#"{"type":"heartbeat","session_id":"abc123"}"#;
+        let req: AgentRequest = serde_json::from_str(json).unwrap();
+        match req {
+            AgentRequest::Heartbeat { session_id } => {
+                assert_eq!(session_id, "abc123");
+            }
+            _ => panic!("wrong variant"),
+        }
+    }
+
     #[test]
     fn test_agent_response_success() {
         let resp = AgentResponse::success(Some(serde_json::json!({"id": "test"})));
@@ -1,65 +1,140 @@
 use rusqlite::{Connection, params};
 use std::path::{Path, PathBuf};
 use crate::error::{KenError, Result};
-use crate::session::{Session, SessionStatus, Event};
-
-const SCHEMA: &str = r#"
-CREATE TABLE IF NOT EXISTS sessions (
-    id TEXT PRIMARY KEY,
-    ken TEXT NOT NULL,
-    task TEXT NOT NULL,
-    status TEXT NOT NULL,
-    parent_id TEXT,
-    trigger TEXT,
-    checkpoint TEXT,
-    result TEXT,
-    created_at TEXT NOT NULL,
-    updated_at TEXT NOT NULL,
-    FOREIGN KEY (parent_id) REFERENCES sessions(id)
-);
-
-CREATE INDEX IF NOT EXISTS idx_sessions_status ON sessions(status);
-CREATE INDEX IF NOT EXISTS idx_sessions_parent ON sessions(parent_id);
-
-CREATE TABLE IF NOT EXISTS events (
-    id INTEGER PRIMARY KEY AUTOINCREMENT,
-    ts TEXT NOT NULL,
-    session_id TEXT,
-    event_type TEXT NOT NULL,
-    data TEXT,
-    FOREIGN KEY (session_id) REFERENCES sessions(id)
-);
-
-CREATE INDEX IF NOT EXISTS idx_events_session ON events(session_id);
-CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
-"#;
+use crate::migrations;
+use crate::retry::RetryPolicy;
+use crate::session::{Session, SessionStatus, SessionAtDepth, Event};
+
+/// Maximum recursion depth for the tree-walking CTEs (`get_descendants`,
+/// `get_ancestors`, `get_subtree`, `count_open_descendants`) - a backstop so
+/// a corrupted `parent_id` cycle can't hang a query instead of erroring out
+/// on an implausibly deep tree.
+const MAX_TREE_DEPTH: i64 = 1000;
+
+/// One operation accumulated into a `Batch`, mirroring the row-level part of
+/// `Storage`'s single-row mutators (`insert_session`, `update_session_status`,
+/// `complete_session`, `sleep_session`, `insert_event`).
+enum BatchOp {
+    InsertSession(Session),
+    UpdateStatus { id: String, status: SessionStatus, updated_at: String },
+    Complete { id: String, result: String, updated_at: String },
+    Sleep { id: String, trigger: String, checkpoint: String, updated_at: String },
+    InsertEvent(Event),
+}
+
+/// Accumulates heterogeneous session/event writes to apply in one
+/// transaction via `Storage::apply_batch`, instead of one round trip per row
+/// (e.g. `spawn_and_sleep`'s per-child `INSERT` loop).
+#[derive(Default)]
+pub struct Batch {
+    ops: Vec<BatchOp>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Batch { ops: Vec::new() }
+    }
+
+    pub fn insert_session(mut self, session: Session) -> Self {
+        self.ops.push(BatchOp::InsertSession(session));
+        self
+    }
+
+    pub fn update_status(mut self, id: &str, status: SessionStatus, updated_at: &str) -> Self {
+        self.ops.push(BatchOp::UpdateStatus {
+            id: id.to_string(),
+            status,
+            updated_at: updated_at.to_string(),
+        });
+        self
+    }
+
+    pub fn complete(mut self, id: &str, result: &str, updated_at: &str) -> Self {
+        self.ops.push(BatchOp::Complete {
+            id: id.to_string(),
+            result: result.to_string(),
+            updated_at: updated_at.to_string(),
+        });
+        self
+    }
+
+    pub fn sleep(mut self, id: &str, trigger: &str, checkpoint: &str, updated_at: &str) -> Self {
+        self.ops.push(BatchOp::Sleep {
+            id: id.to_string(),
+            trigger: trigger.to_string(),
+            checkpoint: checkpoint.to_string(),
+            updated_at: updated_at.to_string(),
+        });
+        self
+    }
+
+    pub fn insert_event(mut self, event: Event) -> Self {
+        self.ops.push(BatchOp::InsertEvent(event));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
 
 /// Storage layer for ken - wraps SQLite database
 pub struct Storage {
     conn: Connection,
+    retry_policy: RetryPolicy,
 }
 
 impl Storage {
-    /// Open existing database
+    /// Open existing database with the default retry policy
     pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with_policy(path, RetryPolicy::default())
+    }
+
+    /// Open existing database, retrying contended statements/transactions
+    /// according to `retry_policy`. Upgrades the database in place by
+    /// running any migrations newer than its stored `PRAGMA user_version`.
+    pub fn open_with_policy(path: &Path, retry_policy: RetryPolicy) -> Result<Self> {
         let conn = Connection::open(path)?;
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
-        Ok(Storage { conn })
+        migrations::apply(&conn)?;
+        Ok(Storage { conn, retry_policy })
     }
 
-    /// Create new database with schema
+    /// Create new database with schema and the default retry policy
     pub fn create(path: &Path) -> Result<Self> {
+        Self::create_with_policy(path, RetryPolicy::default())
+    }
+
+    /// Create new database with schema, retrying contended
+    /// statements/transactions according to `retry_policy`. Simply runs
+    /// every migration starting from version 0.
+    pub fn create_with_policy(path: &Path, retry_policy: RetryPolicy) -> Result<Self> {
         let conn = Connection::open(path)?;
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
-        conn.execute_batch(SCHEMA)?;
-        Ok(Storage { conn })
+        migrations::apply(&conn)?;
+        Ok(Storage { conn, retry_policy })
+    }
+
+    /// The schema version currently applied to this database
+    /// (`PRAGMA user_version`).
+    pub fn schema_version(&self) -> Result<i64> {
+        Ok(self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+    }
+
+    /// The schema version this binary understands and migrates up to.
+    pub fn target_schema_version(&self) -> i64 {
+        migrations::CURRENT_VERSION
     }
 
     /// Insert a new session
     pub fn insert_session(&self, session: &Session) -> Result<()> {
         self.conn.execute(
-            "INSERT INTO sessions (id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO sessions (id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
             params![
                 session.id,
                 session.ken,
@@ -71,6 +146,8 @@ impl Storage {
                 session.result,
                 session.created_at,
                 session.updated_at,
+                session.retry_count,
+                session.max_retries,
             ],
         )?;
         Ok(())
@@ -79,7 +156,7 @@ impl Storage {
     /// Get session by ID
     pub fn get_session(&self, id: &str) -> Result<Session> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at
+            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries
              FROM sessions WHERE id = ?1"
         )?;
 
@@ -95,6 +172,8 @@ impl Storage {
                 result: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
             })
         }).map_err(|_| KenError::SessionNotFound(id.to_string()))?;
 
@@ -104,7 +183,7 @@ impl Storage {
     /// Get sessions by status
     pub fn get_sessions_by_status(&self, status: SessionStatus) -> Result<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at
+            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries
              FROM sessions WHERE status = ?1"
         )?;
 
@@ -120,6 +199,8 @@ impl Storage {
                 result: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
             })
         })?.collect::<std::result::Result<Vec<_>, _>>()?;
 
@@ -129,7 +210,7 @@ impl Storage {
     /// Get all sessions
     pub fn get_all_sessions(&self) -> Result<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at
+            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries
              FROM sessions ORDER BY created_at"
         )?;
 
@@ -145,6 +226,8 @@ impl Storage {
                 result: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
             })
         })?.collect::<std::result::Result<Vec<_>, _>>()?;
 
@@ -154,7 +237,7 @@ impl Storage {
     /// Get children of a session
     pub fn get_children(&self, parent_id: &str) -> Result<Vec<Session>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at
+            "SELECT id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries
              FROM sessions WHERE parent_id = ?1"
         )?;
 
@@ -170,14 +253,176 @@ impl Storage {
                 result: row.get(7)?,
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
             })
         })?.collect::<std::result::Result<Vec<_>, _>>()?;
 
         Ok(sessions)
     }
 
-    /// Update session status
+    /// Get every descendant of `root_id` (children, grandchildren, ...),
+    /// walking `parent_id` downward via a recursive CTE. A session already
+    /// seen on the current path is never revisited, and recursion is capped
+    /// at `MAX_TREE_DEPTH`, so a corrupted `parent_id` cycle can't hang the
+    /// query.
+    pub fn get_descendants(&self, root_id: &str) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE descendants(id, depth, path) AS (
+                SELECT id, 0, '/' || id || '/' FROM sessions WHERE parent_id = ?1
+                UNION ALL
+                SELECT s.id, d.depth + 1, d.path || s.id || '/'
+                FROM sessions s JOIN descendants d ON s.parent_id = d.id
+                WHERE d.depth < ?2 AND d.path NOT LIKE '%/' || s.id || '/%'
+             )
+             SELECT sessions.id, sessions.ken, sessions.task, sessions.status, sessions.parent_id,
+                    sessions.trigger, sessions.checkpoint, sessions.result, sessions.created_at,
+                    sessions.updated_at, sessions.retry_count, sessions.max_retries
+             FROM sessions JOIN descendants ON sessions.id = descendants.id"
+        )?;
+
+        let sessions = stmt.query_map(params![root_id, MAX_TREE_DEPTH], |row| {
+            Ok(Session {
+                id: row.get(0)?,
+                ken: row.get(1)?,
+                task: row.get(2)?,
+                status: SessionStatus::from_str(&row.get::<_, String>(3)?),
+                parent_id: row.get(4)?,
+                trigger: row.get(5)?,
+                checkpoint: row.get(6)?,
+                result: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
+            })
+        })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// Get every ancestor of `id` (parent, grandparent, ... up to the root),
+    /// walking `parent_id` upward via a recursive CTE. Same cycle guard as
+    /// `get_descendants`.
+    pub fn get_ancestors(&self, id: &str) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE ancestors(id, parent_id, depth, path) AS (
+                SELECT s.id, s.parent_id, 0, '/' || s.id || '/' FROM sessions s WHERE s.id = ?1
+                UNION ALL
+                SELECT p.id, p.parent_id, a.depth + 1, a.path || p.id || '/'
+                FROM sessions p JOIN ancestors a ON p.id = a.parent_id
+                WHERE a.depth < ?2 AND a.path NOT LIKE '%/' || p.id || '/%'
+             )
+             SELECT sessions.id, sessions.ken, sessions.task, sessions.status, sessions.parent_id,
+                    sessions.trigger, sessions.checkpoint, sessions.result, sessions.created_at,
+                    sessions.updated_at, sessions.retry_count, sessions.max_retries
+             FROM sessions JOIN ancestors ON sessions.id = ancestors.id
+             WHERE ancestors.id != ?1
+             ORDER BY ancestors.depth"
+        )?;
+
+        let sessions = stmt.query_map(params![id, MAX_TREE_DEPTH], |row| {
+            Ok(Session {
+                id: row.get(0)?,
+                ken: row.get(1)?,
+                task: row.get(2)?,
+                status: SessionStatus::from_str(&row.get::<_, String>(3)?),
+                parent_id: row.get(4)?,
+                trigger: row.get(5)?,
+                checkpoint: row.get(6)?,
+                result: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+                retry_count: row.get(10)?,
+                max_retries: row.get(11)?,
+            })
+        })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// Like `get_descendants`, but each session is annotated with its depth
+    /// below `root_id` (direct children are depth 1).
+    pub fn get_subtree(&self, root_id: &str) -> Result<Vec<SessionAtDepth>> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE descendants(id, depth, path) AS (
+                SELECT id, 1, '/' || id || '/' FROM sessions WHERE parent_id = ?1
+                UNION ALL
+                SELECT s.id, d.depth + 1, d.path || s.id || '/'
+                FROM sessions s JOIN descendants d ON s.parent_id = d.id
+                WHERE d.depth < ?2 AND d.path NOT LIKE '%/' || s.id || '/%'
+             )
+             SELECT sessions.id, sessions.ken, sessions.task, sessions.status, sessions.parent_id,
+                    sessions.trigger, sessions.checkpoint, sessions.result, sessions.created_at,
+                    sessions.updated_at, sessions.retry_count, sessions.max_retries, descendants.depth
+             FROM sessions JOIN descendants ON sessions.id = descendants.id"
+        )?;
+
+        let sessions = stmt.query_map(params![root_id, MAX_TREE_DEPTH], |row| {
+            Ok(SessionAtDepth {
+                session: Session {
+                    id: row.get(0)?,
+                    ken: row.get(1)?,
+                    task: row.get(2)?,
+                    status: SessionStatus::from_str(&row.get::<_, String>(3)?),
+                    parent_id: row.get(4)?,
+                    trigger: row.get(5)?,
+                    checkpoint: row.get(6)?,
+                    result: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    retry_count: row.get(10)?,
+                    max_retries: row.get(11)?,
+                },
+                depth: row.get(12)?,
+            })
+        })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(sessions)
+    }
+
+    /// Count `root_id`'s descendants that are still open (not `Complete` or
+    /// `Failed`), without loading every row - `ken tree` uses it for its
+    /// open-descendant summary.
+    pub fn count_open_descendants(&self, root_id: &str) -> Result<u64> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE descendants(id, depth, path) AS (
+                SELECT id, 0, '/' || id || '/' FROM sessions WHERE parent_id = ?1
+                UNION ALL
+                SELECT s.id, d.depth + 1, d.path || s.id || '/'
+                FROM sessions s JOIN descendants d ON s.parent_id = d.id
+                WHERE d.depth < ?2 AND d.path NOT LIKE '%/' || s.id || '/%'
+             )
+             SELECT COUNT(*) FROM sessions JOIN descendants ON sessions.id = descendants.id
+             WHERE sessions.status NOT IN ('complete', 'failed')"
+        )?;
+
+        let count: u64 = stmt.query_row(params![root_id, MAX_TREE_DEPTH], |row| row.get(0))?;
+        Ok(count)
+    }
+
+    /// Update session status, recording a `session_status_changed` event
+    /// alongside the row update (in one transaction) so the `sessions` table
+    /// stays a materialized view of the `events` log.
     pub fn update_session_status(&self, id: &str, status: SessionStatus, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.begin_transaction()?;
+            let txn_result = (|| {
+                self.update_session_status_row(id, &status, updated_at)?;
+                self.insert_event(&Event {
+                    ts: updated_at.to_string(),
+                    ..Event::new("session_status_changed", Some(id), Some(status.as_str().to_string()))
+                })?;
+                Ok(())
+            })();
+            match txn_result {
+                Ok(()) => { self.commit()?; Ok(()) }
+                Err(e) => { let _ = self.rollback(); Err(e) }
+            }
+        })
+    }
+
+    fn update_session_status_row(&self, id: &str, status: &SessionStatus, updated_at: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE sessions SET status = ?1, updated_at = ?2 WHERE id = ?3",
             params![status.as_str(), updated_at, id],
@@ -185,8 +430,27 @@ impl Storage {
         Ok(())
     }
 
-    /// Update session with result (for complete)
+    /// Update session with result (for complete), recording a
+    /// `session_completed` event alongside the row update in one transaction.
     pub fn complete_session(&self, id: &str, result: &str, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.begin_transaction()?;
+            let txn_result = (|| {
+                self.complete_session_row(id, result, updated_at)?;
+                self.insert_event(&Event {
+                    ts: updated_at.to_string(),
+                    ..Event::new("session_completed", Some(id), Some(result.to_string()))
+                })?;
+                Ok(())
+            })();
+            match txn_result {
+                Ok(()) => { self.commit()?; Ok(()) }
+                Err(e) => { let _ = self.rollback(); Err(e) }
+            }
+        })
+    }
+
+    fn complete_session_row(&self, id: &str, result: &str, updated_at: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE sessions SET status = 'complete', result = ?1, updated_at = ?2 WHERE id = ?3",
             params![result, updated_at, id],
@@ -194,8 +458,51 @@ impl Storage {
         Ok(())
     }
 
-    /// Update session to sleeping with trigger and checkpoint
+    /// Bump a session's `updated_at` without otherwise changing it. Used by
+    /// `AgentRequest::Heartbeat` to prove an `Active` session's agent is alive.
+    pub fn touch_session(&self, id: &str, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.conn.execute(
+                "UPDATE sessions SET updated_at = ?1 WHERE id = ?2",
+                params![updated_at, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Mark a session `Failed`, recording `error` in its `result` column (the
+    /// same column `status` reads back as the failure message).
+    pub fn fail_session(&self, id: &str, error: &str, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.conn.execute(
+                "UPDATE sessions SET status = 'failed', result = ?1, updated_at = ?2 WHERE id = ?3",
+                params![error, updated_at, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Update session to sleeping with trigger and checkpoint, recording a
+    /// `session_sleeping` event alongside the row update in one transaction.
     pub fn sleep_session(&self, id: &str, trigger: &str, checkpoint: &str, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.begin_transaction()?;
+            let txn_result = (|| {
+                self.sleep_session_row(id, trigger, checkpoint, updated_at)?;
+                self.insert_event(&Event {
+                    ts: updated_at.to_string(),
+                    ..Event::new("session_sleeping", Some(id), Some(trigger.to_string()))
+                })?;
+                Ok(())
+            })();
+            match txn_result {
+                Ok(()) => { self.commit()?; Ok(()) }
+                Err(e) => { let _ = self.rollback(); Err(e) }
+            }
+        })
+    }
+
+    fn sleep_session_row(&self, id: &str, trigger: &str, checkpoint: &str, updated_at: &str) -> Result<()> {
         self.conn.execute(
             "UPDATE sessions SET status = 'sleeping', trigger = ?1, checkpoint = ?2, updated_at = ?3 WHERE id = ?4",
             params![trigger, checkpoint, updated_at, id],
@@ -203,6 +510,58 @@ impl Storage {
         Ok(())
     }
 
+    /// Atomically update a session's status, but only if it currently matches
+    /// `expected`. Returns `true` if the update applied, `false` if another
+    /// writer had already moved the session on (e.g. a racing `process` tick).
+    pub fn try_update_session_status(
+        &self,
+        id: &str,
+        expected: SessionStatus,
+        new_status: SessionStatus,
+        updated_at: &str,
+    ) -> Result<bool> {
+        self.retry_policy.retry(|| {
+            let rows = self.conn.execute(
+                "UPDATE sessions SET status = ?1, updated_at = ?2 WHERE id = ?3 AND status = ?4",
+                params![new_status.as_str(), updated_at, id, expected.as_str()],
+            )?;
+            Ok(rows > 0)
+        })
+    }
+
+    /// Overwrite a session's stored trigger JSON without changing its status.
+    /// Used to re-arm recurring (`Schedule`) triggers after they fire.
+    pub fn update_session_trigger(&self, id: &str, trigger: &str, updated_at: &str) -> Result<()> {
+        self.retry_policy.retry(|| {
+            self.conn.execute(
+                "UPDATE sessions SET trigger = ?1, updated_at = ?2 WHERE id = ?3",
+                params![trigger, updated_at, id],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Atomically move a `Failed` session back to `Sleeping` to retry it,
+    /// bumping `retry_count` and installing a fresh backoff `trigger`. Returns
+    /// `true` if applied (the session was still `Failed`), `false` if it had
+    /// already moved on.
+    pub fn retry_session(
+        &self,
+        id: &str,
+        trigger: &str,
+        retry_count: u32,
+        updated_at: &str,
+    ) -> Result<bool> {
+        self.retry_policy.retry(|| {
+            let rows = self.conn.execute(
+                "UPDATE sessions SET status = 'sleeping', trigger = ?1, retry_count = ?2, updated_at = ?3
+                 WHERE id = ?4 AND status = 'failed'",
+                params![trigger, retry_count, updated_at, id],
+            )?;
+            Ok(rows > 0)
+        })
+    }
+
     /// Insert event
     pub fn insert_event(&self, event: &Event) -> Result<()> {
         self.conn.execute(
@@ -212,25 +571,200 @@ impl Storage {
         Ok(())
     }
 
+    /// Get all events recorded for a session, oldest first.
+    pub fn get_events(&self, session_id: &str) -> Result<Vec<Event>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, session_id, event_type, data FROM events WHERE session_id = ?1 ORDER BY id"
+        )?;
+
+        let events = stmt.query_map(params![session_id], |row| {
+            Ok(Event {
+                ts: row.get(0)?,
+                session_id: row.get(1)?,
+                event_type: row.get(2)?,
+                data: row.get(3)?,
+            })
+        })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// Get every event across all sessions at or after `ts`, oldest first -
+    /// the event-sourced complement to `get_events`'s per-session view.
+    pub fn get_events_since(&self, ts: &str) -> Result<Vec<Event>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ts, session_id, event_type, data FROM events WHERE ts >= ?1 ORDER BY id"
+        )?;
+
+        let events = stmt.query_map(params![ts], |row| {
+            Ok(Event {
+                ts: row.get(0)?,
+                session_id: row.get(1)?,
+                event_type: row.get(2)?,
+                data: row.get(3)?,
+            })
+        })?.collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(events)
+    }
+
+    /// Rebuild `id`'s status/trigger/checkpoint/result as they were at `ts`,
+    /// by folding its ordered event log up to that point instead of trusting
+    /// the (possibly since-advanced, or corrupted) `sessions` row. Identity
+    /// fields that never change after creation (`ken`, `task`, `parent_id`,
+    /// `created_at`) are still read straight from the current row, since
+    /// nothing in the event log carries them.
+    ///
+    /// Recognizes both vocabularies that can appear in a real session's
+    /// history: the self-logging transition methods above
+    /// (`update_session_status`/`complete_session`/`sleep_session`/
+    /// `wake_parent`), and the event names `commands::process` logs itself
+    /// around its own `try_update_session_status`/`retry_session` calls
+    /// (`session_activated`, `trigger_satisfied`, `session_stale`,
+    /// `trigger_rearmed`, `session_retry`).
+    ///
+    /// `children_spawned` (the event `spawn_and_sleep` logs) is treated as a
+    /// sleep transition but can't recover the exact `trigger` that was
+    /// installed, since that event's payload is the spawned child IDs, not
+    /// the trigger JSON - a known gap until that event is enriched.
+    pub fn reconstruct_session_at(&self, id: &str, ts: &str) -> Result<Session> {
+        let current = self.get_session(id)?;
+        let events = self.get_events(id)?;
+
+        let mut session = Session {
+            status: SessionStatus::Pending,
+            trigger: None,
+            checkpoint: None,
+            result: None,
+            updated_at: current.created_at.clone(),
+            ..current
+        };
+
+        for event in events.iter().filter(|e| e.ts.as_str() <= ts) {
+            session.updated_at = event.ts.clone();
+            match event.event_type.as_str() {
+                "session_completed" => {
+                    session.status = SessionStatus::Complete;
+                    session.result = event.data.clone();
+                }
+                "session_failed" => {
+                    session.status = SessionStatus::Failed;
+                    session.result = event.data.clone();
+                }
+                "session_sleeping" => {
+                    session.status = SessionStatus::Sleeping;
+                    session.trigger = event.data.clone();
+                }
+                "children_spawned" => {
+                    session.status = SessionStatus::Sleeping;
+                }
+                "session_status_changed" => {
+                    if let Some(status) = &event.data {
+                        session.status = SessionStatus::from_str(status);
+                    }
+                }
+                "trigger_satisfied" => {
+                    session.status = SessionStatus::Pending;
+                }
+                "session_activated" => {
+                    session.status = SessionStatus::Active;
+                }
+                "session_stale" => {
+                    session.status = SessionStatus::Failed;
+                    session.result = event.data.clone();
+                }
+                "trigger_rearmed" => {
+                    session.trigger = event.data.clone();
+                }
+                "session_retry" => {
+                    session.status = SessionStatus::Sleeping;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(session)
+    }
+
+    /// Atomically wake a `Sleeping` parent whose trigger just fired: flip it
+    /// to `Pending`, the same intermediate state `process::wake_satisfied_sessions`
+    /// uses for the polling path, and emit a `trigger_satisfied` event
+    /// carrying its trigger JSON - all inside one transaction, so a crash
+    /// between the row update and the event can't leave a parent stuck
+    /// asleep with no trace of waking. Landing on `Pending` rather than
+    /// jumping straight to `Active` means the next `process`/`daemon` pass
+    /// is the one that actually activates the session, emits the
+    /// `spawn`/execute signal, and aggregates `children_results` - so this
+    /// immediate-wake path doesn't need its own copy of that logic, and a
+    /// parent woken here is never left `Active` with no one told to run its
+    /// agent. The `trigger` column is deliberately left in place (not
+    /// cleared) so the activation pass can still read it. Returns `true` if
+    /// the parent was woken, `false` if it wasn't `Sleeping` any more.
+    pub fn wake_parent(&self, id: &str, updated_at: &str) -> Result<bool> {
+        self.begin_transaction()?;
+
+        let result = (|| {
+            let rows = self.conn.execute(
+                "UPDATE sessions SET status = 'pending', updated_at = ?1
+                 WHERE id = ?2 AND status = 'sleeping'",
+                params![updated_at, id],
+            )?;
+
+            if rows > 0 {
+                let trigger = self.get_session(id)?.trigger;
+                self.insert_event(&Event::new("trigger_satisfied", Some(id), trigger))?;
+            }
+
+            Ok(rows > 0)
+        })();
+
+        match result {
+            Ok(woke) => {
+                self.commit()?;
+                Ok(woke)
+            }
+            Err(e) => {
+                let _ = self.rollback();
+                Err(e)
+            }
+        }
+    }
+
     /// Begin transaction
     pub fn begin_transaction(&self) -> Result<()> {
-        self.conn.execute("BEGIN", [])?;
-        Ok(())
+        self.retry_policy.retry(|| {
+            self.conn.execute("BEGIN", [])?;
+            Ok(())
+        })
     }
 
     /// Commit transaction
     pub fn commit(&self) -> Result<()> {
-        self.conn.execute("COMMIT", [])?;
-        Ok(())
+        self.retry_policy.retry(|| {
+            self.conn.execute("COMMIT", [])?;
+            Ok(())
+        })
     }
 
     /// Rollback transaction
     pub fn rollback(&self) -> Result<()> {
-        self.conn.execute("ROLLBACK", [])?;
-        Ok(())
+        self.retry_policy.retry(|| {
+            self.conn.execute("ROLLBACK", [])?;
+            Ok(())
+        })
     }
 
-    /// Execute atomic spawn_and_sleep operation
+    /// Execute atomic spawn_and_sleep operation. Retries the whole
+    /// begin/insert/sleep/commit body (not just one statement within it) so a
+    /// transaction that got rolled back due to contention is cleanly re-run
+    /// from scratch rather than partially applied.
+    ///
+    /// Builds a `Batch` of the per-child `InsertSession` ops plus the
+    /// parent's `Sleep` and `children_spawned` `InsertEvent`, and applies it
+    /// via the row-only `apply_batch_rows` (not the public `apply_batch`,
+    /// which would try to open a second, nested transaction) - collapsing
+    /// what used to be one round trip per child into the same single
+    /// prepared-statement-per-kind pass `apply_batch` uses elsewhere.
     pub fn spawn_and_sleep(
         &self,
         parent_id: &str,
@@ -239,41 +773,127 @@ impl Storage {
         checkpoint: &str,
         updated_at: &str,
     ) -> Result<Vec<String>> {
-        self.begin_transaction()?;
-
-        let result = (|| {
-            let mut child_ids = Vec::new();
-
-            // Insert all children
-            for child in &children {
-                self.insert_session(child)?;
-                child_ids.push(child.id.clone());
+        self.retry_policy.retry(|| {
+            self.begin_transaction()?;
+
+            let result = (|| {
+                let child_ids: Vec<String> = children.iter().map(|c| c.id.clone()).collect();
+
+                let mut batch = Batch::new();
+                for child in &children {
+                    batch = batch.insert_session(child.clone());
+                }
+                batch = batch.sleep(parent_id, trigger, checkpoint, updated_at);
+                batch = batch.insert_event(Event {
+                    ts: updated_at.to_string(),
+                    session_id: Some(parent_id.to_string()),
+                    event_type: "children_spawned".to_string(),
+                    data: Some(serde_json::to_string(&child_ids)?),
+                });
+
+                self.apply_batch_rows(&batch)?;
+
+                Ok(child_ids)
+            })();
+
+            match result {
+                Ok(ids) => {
+                    self.commit()?;
+                    Ok(ids)
+                }
+                Err(e) => {
+                    let _ = self.rollback();
+                    Err(e)
+                }
             }
+        })
+    }
 
-            // Update parent to sleeping
-            self.sleep_session(parent_id, trigger, checkpoint, updated_at)?;
-
-            // Log event
-            self.insert_event(&Event {
-                ts: updated_at.to_string(),
-                session_id: Some(parent_id.to_string()),
-                event_type: "children_spawned".to_string(),
-                data: Some(serde_json::to_string(&child_ids)?),
-            })?;
+    /// Apply every operation in `batch` inside a single transaction, using
+    /// one prepared statement per operation kind reused across all rows
+    /// (rather than repreparing per row), and returning the number of rows
+    /// each operation affected, in the same order the operation was added.
+    /// All-or-nothing like `spawn_and_sleep`: if any operation fails the
+    /// whole transaction rolls back and the error propagates instead of a
+    /// partial result vector.
+    pub fn apply_batch(&self, batch: Batch) -> Result<Vec<usize>> {
+        self.retry_policy.retry(|| {
+            self.begin_transaction()?;
+
+            let txn_result = self.apply_batch_rows(&batch);
+
+            match txn_result {
+                Ok(results) => {
+                    self.commit()?;
+                    Ok(results)
+                }
+                Err(e) => {
+                    let _ = self.rollback();
+                    Err(e)
+                }
+            }
+        })
+    }
 
-            Ok(child_ids)
-        })();
+    /// Row-only core of `apply_batch`, with no transaction of its own -
+    /// callers that are already inside their own transaction (e.g.
+    /// `spawn_and_sleep`) use this directly instead, since SQLite doesn't
+    /// nest `BEGIN`s.
+    fn apply_batch_rows(&self, batch: &Batch) -> Result<Vec<usize>> {
+        let mut insert_session_stmt = self.conn.prepare(
+            "INSERT INTO sessions (id, ken, task, status, parent_id, trigger, checkpoint, result, created_at, updated_at, retry_count, max_retries)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)"
+        )?;
+        let mut update_status_stmt = self.conn.prepare(
+            "UPDATE sessions SET status = ?1, updated_at = ?2 WHERE id = ?3"
+        )?;
+        let mut complete_stmt = self.conn.prepare(
+            "UPDATE sessions SET status = 'complete', result = ?1, updated_at = ?2 WHERE id = ?3"
+        )?;
+        let mut sleep_stmt = self.conn.prepare(
+            "UPDATE sessions SET status = 'sleeping', trigger = ?1, checkpoint = ?2, updated_at = ?3 WHERE id = ?4"
+        )?;
+        let mut insert_event_stmt = self.conn.prepare(
+            "INSERT INTO events (ts, session_id, event_type, data) VALUES (?1, ?2, ?3, ?4)"
+        )?;
 
-        match result {
-            Ok(ids) => {
-                self.commit()?;
-                Ok(ids)
-            }
-            Err(e) => {
-                let _ = self.rollback();
-                Err(e)
-            }
+        let mut results = Vec::with_capacity(batch.ops.len());
+        for op in &batch.ops {
+            let rows = match op {
+                BatchOp::InsertSession(session) => insert_session_stmt.execute(params![
+                    session.id,
+                    session.ken,
+                    session.task,
+                    session.status.as_str(),
+                    session.parent_id,
+                    session.trigger,
+                    session.checkpoint,
+                    session.result,
+                    session.created_at,
+                    session.updated_at,
+                    session.retry_count,
+                    session.max_retries,
+                ])?,
+                BatchOp::UpdateStatus { id, status, updated_at } => {
+                    update_status_stmt.execute(params![status.as_str(), updated_at, id])?
+                }
+                BatchOp::Complete { id, result, updated_at } => {
+                    complete_stmt.execute(params![result, updated_at, id])?
+                }
+                BatchOp::Sleep { id, trigger, checkpoint, updated_at } => {
+                    sleep_stmt.execute(params![trigger, checkpoint, updated_at, id])?
+                }
+                BatchOp::InsertEvent(event) => insert_event_stmt.execute(params![
+                    event.ts,
+                    event.session_id,
+                    event.event_type,
+                    event.data,
+                ])?,
+            };
+            results.push(rows);
         }
+
+        Ok(results)
     }
 }
 
@@ -299,6 +919,12 @@ pub fn get_db_path() -> Result<PathBuf> {
     Ok(ken_dir.join("ken.db"))
 }
 
+/// Get the project root (the directory containing `.ken/`)
+pub fn find_project_root() -> Result<PathBuf> {
+    let ken_dir = find_ken_dir()?;
+    Ok(ken_dir.parent().expect("`.ken` always has a parent").to_path_buf())
+}
+
 /// Open the storage (finds .ken dir automatically)
 pub fn open_storage() -> Result<Storage> {
     let db_path = get_db_path()?;
@@ -389,6 +1015,38 @@ mod tests {
         assert_eq!(retrieved.result, Some("done!".to_string()));
     }
 
+    #[test]
+    fn test_touch_session() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::new("test-ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        storage.touch_session(&session.id, &now).unwrap();
+
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.updated_at, now);
+        assert_eq!(retrieved.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_fail_session() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::new("test-ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        storage.fail_session(&session.id, "agent timed out", &now).unwrap();
+
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Failed);
+        assert_eq!(retrieved.result, Some("agent timed out".to_string()));
+    }
+
     #[test]
     fn test_sleep_session() {
         let (storage, _dir) = create_test_storage();
@@ -407,6 +1065,136 @@ mod tests {
         assert_eq!(retrieved.checkpoint, Some("my checkpoint".to_string()));
     }
 
+    #[test]
+    fn test_try_update_session_status_applies_when_expected_matches() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::new("test-ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let applied = storage.try_update_session_status(
+            &session.id,
+            SessionStatus::Pending,
+            SessionStatus::Active,
+            &now,
+        ).unwrap();
+
+        assert!(applied);
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_try_update_session_status_rejects_when_expected_mismatches() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::new("test-ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let applied = storage.try_update_session_status(
+            &session.id,
+            SessionStatus::Pending,
+            SessionStatus::Complete,
+            &now,
+        ).unwrap();
+
+        assert!(!applied);
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Active);
+    }
+
+    #[test]
+    fn test_update_session_trigger() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::new("test-ken", "test task", None);
+        session.status = SessionStatus::Sleeping;
+        session.trigger = Some(r#"{"schedule":{"cron":"* * * * *","last_fired":null}}"#.to_string());
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let rearmed = format!(r#"{{"schedule":{{"cron":"* * * * *","last_fired":"{}"}}}}"#, now);
+        storage.update_session_trigger(&session.id, &rearmed, &now).unwrap();
+
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.trigger, Some(rearmed));
+        assert_eq!(retrieved.status, SessionStatus::Sleeping);
+    }
+
+    #[test]
+    fn test_retry_session_moves_failed_to_sleeping() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::new("test-ken", "test task", None);
+        session.status = SessionStatus::Failed;
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let trigger = r#"{"timeout_seconds":30}"#;
+        let applied = storage.retry_session(&session.id, trigger, 1, &now).unwrap();
+
+        assert!(applied);
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Sleeping);
+        assert_eq!(retrieved.trigger, Some(trigger.to_string()));
+        assert_eq!(retrieved.retry_count, 1);
+    }
+
+    #[test]
+    fn test_retry_session_rejects_if_not_failed() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::new("test-ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let applied = storage.retry_session(&session.id, r#"{"timeout_seconds":30}"#, 1, &now).unwrap();
+
+        assert!(!applied);
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Pending);
+    }
+
+    #[test]
+    fn test_wake_parent_flips_sleeping_to_pending_and_keeps_trigger() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut parent = Session::new("parent-ken", "parent task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        parent.checkpoint = Some("my checkpoint".to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let woke = storage.wake_parent(&parent.id, &now).unwrap();
+
+        assert!(woke);
+        let retrieved = storage.get_session(&parent.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Pending);
+        assert_eq!(retrieved.trigger, parent.trigger);
+
+        let events = storage.get_events(&parent.id).unwrap();
+        assert!(events.iter().any(|e| e.event_type == "trigger_satisfied"));
+    }
+
+    #[test]
+    fn test_wake_parent_no_op_if_not_sleeping() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::new("test-ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let woke = storage.wake_parent(&session.id, &now).unwrap();
+
+        assert!(!woke);
+        let retrieved = storage.get_session(&session.id).unwrap();
+        assert_eq!(retrieved.status, SessionStatus::Pending);
+    }
+
     #[test]
     fn test_get_children() {
         let (storage, _dir) = create_test_storage();
@@ -423,6 +1211,102 @@ mod tests {
         assert_eq!(children.len(), 2);
     }
 
+    #[test]
+    fn test_get_descendants_returns_entire_subtree() {
+        let (storage, _dir) = create_test_storage();
+
+        let root = Session::with_id("root", "root/ken", "root task", None);
+        let child = Session::with_id("child", "child/ken", "child task", Some("root".to_string()));
+        let grandchild = Session::with_id("grandchild", "gc/ken", "gc task", Some("child".to_string()));
+        storage.insert_session(&root).unwrap();
+        storage.insert_session(&child).unwrap();
+        storage.insert_session(&grandchild).unwrap();
+
+        let mut descendants = storage.get_descendants("root").unwrap();
+        descendants.sort_by(|a, b| a.id.cmp(&b.id));
+        let ids: Vec<&str> = descendants.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["child", "grandchild"]);
+    }
+
+    #[test]
+    fn test_get_descendants_empty_for_leaf_session() {
+        let (storage, _dir) = create_test_storage();
+
+        let leaf = Session::with_id("leaf", "leaf/ken", "leaf task", None);
+        storage.insert_session(&leaf).unwrap();
+
+        let descendants = storage.get_descendants("leaf").unwrap();
+        assert!(descendants.is_empty());
+    }
+
+    #[test]
+    fn test_get_ancestors_returns_path_to_root() {
+        let (storage, _dir) = create_test_storage();
+
+        let root = Session::with_id("root", "root/ken", "root task", None);
+        let child = Session::with_id("child", "child/ken", "child task", Some("root".to_string()));
+        let grandchild = Session::with_id("grandchild", "gc/ken", "gc task", Some("child".to_string()));
+        storage.insert_session(&root).unwrap();
+        storage.insert_session(&child).unwrap();
+        storage.insert_session(&grandchild).unwrap();
+
+        let mut ancestors = storage.get_ancestors("grandchild").unwrap();
+        ancestors.sort_by(|a, b| a.id.cmp(&b.id));
+        let ids: Vec<&str> = ancestors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["child", "root"]);
+    }
+
+    #[test]
+    fn test_get_ancestors_empty_for_root_session() {
+        let (storage, _dir) = create_test_storage();
+
+        let root = Session::with_id("root", "root/ken", "root task", None);
+        storage.insert_session(&root).unwrap();
+
+        let ancestors = storage.get_ancestors("root").unwrap();
+        assert!(ancestors.is_empty());
+    }
+
+    #[test]
+    fn test_get_subtree_annotates_depth() {
+        let (storage, _dir) = create_test_storage();
+
+        let root = Session::with_id("root", "root/ken", "root task", None);
+        let child = Session::with_id("child", "child/ken", "child task", Some("root".to_string()));
+        let grandchild = Session::with_id("grandchild", "gc/ken", "gc task", Some("child".to_string()));
+        storage.insert_session(&root).unwrap();
+        storage.insert_session(&child).unwrap();
+        storage.insert_session(&grandchild).unwrap();
+
+        let subtree = storage.get_subtree("root").unwrap();
+        let depth_by_id: std::collections::HashMap<&str, u32> = subtree
+            .iter()
+            .map(|s| (s.session.id.as_str(), s.depth))
+            .collect();
+        assert_eq!(depth_by_id["child"], 1);
+        assert_eq!(depth_by_id["grandchild"], 2);
+    }
+
+    #[test]
+    fn test_count_open_descendants_excludes_complete_and_failed() {
+        let (storage, _dir) = create_test_storage();
+
+        let root = Session::with_id("root", "root/ken", "root task", None);
+        let mut done = Session::with_id("done", "done/ken", "done task", Some("root".to_string()));
+        done.status = SessionStatus::Complete;
+        let mut failed = Session::with_id("dead", "dead/ken", "dead task", Some("root".to_string()));
+        failed.status = SessionStatus::Failed;
+        let pending = Session::with_id("pending", "pending/ken", "pending task", Some("root".to_string()));
+
+        storage.insert_session(&root).unwrap();
+        storage.insert_session(&done).unwrap();
+        storage.insert_session(&failed).unwrap();
+        storage.insert_session(&pending).unwrap();
+
+        let open = storage.count_open_descendants("root").unwrap();
+        assert_eq!(open, 1);
+    }
+
     #[test]
     fn test_spawn_and_sleep_atomic() {
         let (storage, _dir) = create_test_storage();
@@ -477,6 +1361,140 @@ mod tests {
         // Event inserted successfully
     }
 
+    #[test]
+    fn test_get_events_returns_in_order() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::with_id("test-session", "test/ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        storage.insert_event(&Event::new("session_created", Some("test-session"), None)).unwrap();
+        storage.insert_event(&Event::new("session_activated", Some("test-session"), None)).unwrap();
+
+        let events = storage.get_events("test-session").unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event_type, "session_created");
+        assert_eq!(events[1].event_type, "session_activated");
+    }
+
+    #[test]
+    fn test_get_events_empty_for_unknown_session() {
+        let (storage, _dir) = create_test_storage();
+
+        let events = storage.get_events("no-such-session").unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn test_get_events_since_spans_sessions_and_respects_cutoff() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::with_id("test-session", "test/ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        storage.insert_event(&Event { ts: "2024-01-01T00:00:00+00:00".to_string(), session_id: Some("test-session".to_string()), event_type: "early".to_string(), data: None }).unwrap();
+        storage.insert_event(&Event { ts: "2024-06-01T00:00:00+00:00".to_string(), session_id: Some("test-session".to_string()), event_type: "late".to_string(), data: None }).unwrap();
+
+        let since = storage.get_events_since("2024-03-01T00:00:00+00:00").unwrap();
+        assert_eq!(since.len(), 1);
+        assert_eq!(since[0].event_type, "late");
+    }
+
+    #[test]
+    fn test_complete_session_logs_session_completed_event() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::with_id("test-session", "test/ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        storage.complete_session("test-session", "all done", "2024-01-01T00:00:00+00:00").unwrap();
+
+        let events = storage.get_events("test-session").unwrap();
+        assert!(events.iter().any(|e| e.event_type == "session_completed" && e.data == Some("all done".to_string())));
+    }
+
+    #[test]
+    fn test_sleep_session_logs_session_sleeping_event() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::with_id("test-session", "test/ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        storage.sleep_session("test-session", r#"{"timeout_seconds":30}"#, "checkpoint", "2024-01-01T00:00:00+00:00").unwrap();
+
+        let events = storage.get_events("test-session").unwrap();
+        assert!(events.iter().any(|e| e.event_type == "session_sleeping"));
+    }
+
+    #[test]
+    fn test_reconstruct_session_at_rebuilds_status_as_of_timestamp() {
+        let (storage, _dir) = create_test_storage();
+
+        let mut session = Session::with_id("test-session", "test/ken", "test task", None);
+        session.status = SessionStatus::Active;
+        storage.insert_session(&session).unwrap();
+
+        storage.sleep_session("test-session", r#"{"timeout_seconds":30}"#, "my checkpoint", "2024-01-01T00:00:00+00:00").unwrap();
+        storage.complete_session("test-session", "done", "2024-06-01T00:00:00+00:00").unwrap();
+
+        let mid = storage.reconstruct_session_at("test-session", "2024-03-01T00:00:00+00:00").unwrap();
+        assert_eq!(mid.status, SessionStatus::Sleeping);
+        assert_eq!(mid.trigger, Some(r#"{"timeout_seconds":30}"#.to_string()));
+
+        let after = storage.reconstruct_session_at("test-session", "2024-12-01T00:00:00+00:00").unwrap();
+        assert_eq!(after.status, SessionStatus::Complete);
+        assert_eq!(after.result, Some("done".to_string()));
+    }
+
+    #[test]
+    fn test_reconstruct_session_at_before_any_events_is_pending() {
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::with_id("test-session", "test/ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+        storage.sleep_session("test-session", r#"{"timeout_seconds":30}"#, "checkpoint", "2024-06-01T00:00:00+00:00").unwrap();
+
+        let before = storage.reconstruct_session_at("test-session", "2024-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(before.status, SessionStatus::Pending);
+        assert!(before.trigger.is_none());
+    }
+
+    #[test]
+    fn test_reconstruct_session_at_understands_process_command_event_vocabulary() {
+        // `commands::process` drives its real transitions through
+        // `try_update_session_status` directly, logging its own event names
+        // rather than going through `update_session_status`/`complete_session`/
+        // etc. Replay needs to recognize that vocabulary too, not just the
+        // one the self-logging methods above produce.
+        let (storage, _dir) = create_test_storage();
+
+        let session = Session::with_id("test-session", "test/ken", "test task", None);
+        storage.insert_session(&session).unwrap();
+
+        storage.insert_event(&Event::new(
+            "trigger_satisfied",
+            Some("test-session"),
+            Some(r#"{"timeout_seconds":30}"#.to_string()),
+        )).unwrap();
+        let satisfied = storage.reconstruct_session_at("test-session", "9999-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(satisfied.status, SessionStatus::Pending);
+
+        storage.insert_event(&Event::new("session_activated", Some("test-session"), None)).unwrap();
+        let activated = storage.reconstruct_session_at("test-session", "9999-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(activated.status, SessionStatus::Active);
+
+        storage.insert_event(&Event::new(
+            "session_stale",
+            Some("test-session"),
+            Some("no update in 120s (timeout: 60s)".to_string()),
+        )).unwrap();
+        let stale = storage.reconstruct_session_at("test-session", "9999-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(stale.status, SessionStatus::Failed);
+        assert_eq!(stale.result, Some("no update in 120s (timeout: 60s)".to_string()));
+    }
+
     #[test]
     fn test_insert_event_without_session() {
         let (storage, _dir) = create_test_storage();
@@ -491,4 +1509,111 @@ mod tests {
 
         storage.insert_event(&event).unwrap();
     }
+
+    #[test]
+    fn test_spawn_and_sleep_works_under_default_retry_policy() {
+        // Wrapping spawn_and_sleep's body in retry_policy.retry() shouldn't
+        // change its behavior on the (much more common) non-contended path.
+        let (storage, _dir) = create_test_storage();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "parent task", None);
+        parent.status = SessionStatus::Active;
+        storage.insert_session(&parent).unwrap();
+
+        let children = vec![Session::with_id("child-1", "child/ken", "child task", Some("parent-1".to_string()))];
+        let ids = storage
+            .spawn_and_sleep("parent-1", children, r#"{"all_complete":["child-1"]}"#, "checkpoint", "2024-01-01T00:00:00+00:00")
+            .unwrap();
+
+        assert_eq!(ids, vec!["child-1".to_string()]);
+        let parent = storage.get_session("parent-1").unwrap();
+        assert_eq!(parent.status, SessionStatus::Sleeping);
+    }
+
+    #[test]
+    fn test_schema_version_matches_target_after_create() {
+        let (storage, _dir) = create_test_storage();
+        assert_eq!(storage.schema_version().unwrap(), storage.target_schema_version());
+    }
+
+    #[test]
+    fn test_open_rejects_database_newer_than_binary_supports() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("ken.db");
+
+        {
+            let conn = Connection::open(&db_path).unwrap();
+            conn.execute_batch(&format!("PRAGMA user_version = {}", crate::migrations::CURRENT_VERSION + 1)).unwrap();
+        }
+
+        let result = Storage::open(&db_path);
+        assert!(matches!(result, Err(KenError::SchemaTooNew { .. })));
+    }
+
+    #[test]
+    fn test_open_with_policy_and_create_with_policy_apply_custom_policy() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("ken.db");
+
+        let storage = Storage::create_with_policy(&db_path, RetryPolicy::no_retry()).unwrap();
+        drop(storage);
+
+        let storage = Storage::open_with_policy(&db_path, RetryPolicy::no_retry()).unwrap();
+        let sessions = storage.get_all_sessions().unwrap();
+        assert!(sessions.is_empty());
+    }
+
+    #[test]
+    fn test_apply_batch_inserts_many_children_and_an_event_in_one_transaction() {
+        let (storage, _dir) = create_test_storage();
+
+        let parent = Session::with_id("parent-1", "parent/ken", "parent task", None);
+        storage.insert_session(&parent).unwrap();
+
+        let batch = Batch::new()
+            .insert_session(Session::with_id("child-1", "child/ken", "task", Some("parent-1".to_string())))
+            .insert_session(Session::with_id("child-2", "child/ken", "task", Some("parent-1".to_string())))
+            .update_status("parent-1", SessionStatus::Active, "2024-01-01T00:00:00+00:00")
+            .insert_event(Event::new("batch_test", Some("parent-1"), None));
+
+        let results = storage.apply_batch(batch).unwrap();
+        assert_eq!(results, vec![1, 1, 1, 1]);
+
+        let children = storage.get_children("parent-1").unwrap();
+        assert_eq!(children.len(), 2);
+
+        let parent = storage.get_session("parent-1").unwrap();
+        assert_eq!(parent.status, SessionStatus::Active);
+
+        let events = storage.get_events("parent-1").unwrap();
+        assert!(events.iter().any(|e| e.event_type == "batch_test"));
+    }
+
+    #[test]
+    fn test_apply_batch_rolls_back_entirely_on_failure() {
+        let (storage, _dir) = create_test_storage();
+
+        let batch = Batch::new()
+            .insert_session(Session::with_id("ok-session", "test/ken", "task", None))
+            // Duplicate primary key - this insert will fail
+            .insert_session(Session::with_id("ok-session", "test/ken", "task", None));
+
+        let result = storage.apply_batch(batch);
+        assert!(result.is_err());
+
+        let sessions = storage.get_all_sessions().unwrap();
+        assert!(sessions.is_empty());
+    }
+
+    #[test]
+    fn test_apply_batch_complete_and_sleep_report_zero_rows_for_unknown_id() {
+        let (storage, _dir) = create_test_storage();
+
+        let batch = Batch::new()
+            .complete("no-such-session", "result", "2024-01-01T00:00:00+00:00")
+            .sleep("no-such-session", "{}", "checkpoint", "2024-01-01T00:00:00+00:00");
+
+        let results = storage.apply_batch(batch).unwrap();
+        assert_eq!(results, vec![0, 0]);
+    }
 }
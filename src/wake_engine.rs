@@ -0,0 +1,212 @@
+use crate::error::Result;
+use crate::session::{Session, SessionStatus, Trigger};
+use crate::storage::Storage;
+
+/// Called whenever the engine wakes a parent session, with the snapshot of
+/// that parent from just before it was woken (now `Pending`, ready for the
+/// next activation pass).
+pub type WakeObserver = Box<dyn Fn(&Session) + Send + Sync>;
+
+/// Reacts to terminal child-session transitions by evaluating and firing a
+/// sleeping parent's trigger immediately, instead of waiting for the next
+/// polling `process` tick to notice. Complements (rather than replaces)
+/// `commands::process::wake_satisfied_sessions`, which remains the fallback
+/// for time-based triggers this engine doesn't drive.
+#[derive(Default)]
+pub struct WakeEngine {
+    observers: Vec<WakeObserver>,
+}
+
+impl WakeEngine {
+    pub fn new() -> Self {
+        WakeEngine { observers: Vec::new() }
+    }
+
+    /// Register a callback to run every time this engine wakes a parent -
+    /// modeled on a transaction-observation service, where interested code
+    /// subscribes once rather than the engine needing to know its callers.
+    pub fn subscribe(&mut self, observer: WakeObserver) {
+        self.observers.push(observer);
+    }
+
+    /// Call this after `child_id` transitions to `Complete` or `Failed`.
+    /// Looks up its parent and, if sleeping with a now-satisfied trigger,
+    /// wakes it.
+    pub fn on_terminal_transition(&self, storage: &Storage, child_id: &str, now: &str) -> Result<()> {
+        let child = match storage.get_session(child_id) {
+            Ok(child) => child,
+            Err(_) => return Ok(()),
+        };
+
+        let parent_id = match child.parent_id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+
+        self.evaluate_parent(storage, &parent_id, now)
+    }
+
+    /// Evaluate one parent's trigger directly - the shared core of
+    /// `on_terminal_transition`, also usable by callers that already know
+    /// the parent id (e.g. a `daemon` tick re-checking sleepers).
+    pub fn evaluate_parent(&self, storage: &Storage, parent_id: &str, now: &str) -> Result<()> {
+        let parent = match storage.get_session(parent_id) {
+            Ok(parent) => parent,
+            Err(_) => return Ok(()),
+        };
+
+        if parent.status != SessionStatus::Sleeping {
+            return Ok(());
+        }
+
+        let trigger_json = match &parent.trigger {
+            Some(t) => t,
+            None => return Ok(()),
+        };
+
+        let trigger = match Trigger::from_json(trigger_json) {
+            Ok(t) => t,
+            Err(_) => return Ok(()),
+        };
+
+        let satisfied = trigger.is_satisfied(|id| storage.get_session(id).ok().map(|s| s.status));
+        if !satisfied {
+            return Ok(());
+        }
+
+        let woke = storage.wake_parent(parent_id, now)?;
+        if woke {
+            for observer in &self.observers {
+                observer(&parent);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Session;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn create_test_storage() -> (tempfile::TempDir, Storage) {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("ken.db");
+        let storage = Storage::create(&db_path).unwrap();
+        (dir, storage)
+    }
+
+    #[test]
+    fn test_wakes_parent_when_all_children_complete() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "task", Some("parent-1".to_string()));
+        child.status = SessionStatus::Complete;
+        storage.insert_session(&child).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let engine = WakeEngine::new();
+        let now = chrono::Utc::now().to_rfc3339();
+        engine.on_terminal_transition(&storage, "child-1", &now).unwrap();
+
+        let updated = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Pending);
+        assert!(updated.trigger.is_some());
+    }
+
+    #[test]
+    fn test_does_not_wake_parent_when_trigger_unsatisfied() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child1 = Session::with_id("child-1", "child/ken", "task", Some("parent-1".to_string()));
+        child1.status = SessionStatus::Complete;
+        storage.insert_session(&child1).unwrap();
+
+        let child2 = Session::with_id("child-2", "child/ken", "task", Some("parent-1".to_string()));
+        storage.insert_session(&child2).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1","child-2"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let engine = WakeEngine::new();
+        let now = chrono::Utc::now().to_rfc3339();
+        engine.on_terminal_transition(&storage, "child-1", &now).unwrap();
+
+        let updated = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Sleeping);
+    }
+
+    #[test]
+    fn test_wakes_parent_on_count_trigger() {
+        let (_dir, storage) = create_test_storage();
+
+        for (id, status) in [("c1", SessionStatus::Complete), ("c2", SessionStatus::Complete), ("c3", SessionStatus::Active)] {
+            let mut child = Session::with_id(id, "child/ken", "task", Some("parent-1".to_string()));
+            child.status = status;
+            storage.insert_session(&child).unwrap();
+        }
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"count":{"n":2,"children":["c1","c2","c3"]}}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let engine = WakeEngine::new();
+        let now = chrono::Utc::now().to_rfc3339();
+        engine.on_terminal_transition(&storage, "c2", &now).unwrap();
+
+        let updated = storage.get_session("parent-1").unwrap();
+        assert_eq!(updated.status, SessionStatus::Pending);
+    }
+
+    #[test]
+    fn test_notifies_subscribed_observers_on_wake() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "task", Some("parent-1".to_string()));
+        child.status = SessionStatus::Complete;
+        storage.insert_session(&child).unwrap();
+
+        let mut parent = Session::with_id("parent-1", "parent/ken", "task", None);
+        parent.status = SessionStatus::Sleeping;
+        parent.trigger = Some(r#"{"all_complete":["child-1"]}"#.to_string());
+        storage.insert_session(&parent).unwrap();
+
+        let woken_count = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&woken_count);
+        let mut engine = WakeEngine::new();
+        engine.subscribe(Box::new(move |_session| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let now = chrono::Utc::now().to_rfc3339();
+        engine.on_terminal_transition(&storage, "child-1", &now).unwrap();
+
+        assert_eq!(woken_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_no_op_when_child_has_no_parent() {
+        let (_dir, storage) = create_test_storage();
+
+        let mut child = Session::with_id("child-1", "child/ken", "task", None);
+        child.status = SessionStatus::Complete;
+        storage.insert_session(&child).unwrap();
+
+        let engine = WakeEngine::new();
+        let now = chrono::Utc::now().to_rfc3339();
+        let result = engine.on_terminal_transition(&storage, "child-1", &now);
+
+        assert!(result.is_ok());
+    }
+}